@@ -0,0 +1,176 @@
+use std::io::IsTerminal;
+
+use crate::diagnostics::{ Diagnostic, Label };
+use crate::source::{ FileId, SourceMap };
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// Renders diagnostics rustc-style, with the offending source lines and the
+/// labelled spans underlined beneath them.
+pub struct Emitter {
+    color: bool,
+}
+
+impl Emitter {
+    pub fn new(color: bool) -> Emitter {
+        Emitter {
+            color,
+        }
+    }
+
+    /// Creates an emitter that colours its output only when stderr is a terminal.
+    ///
+    /// This checks stderr rather than stdout because that's where diagnostics
+    /// are written: `indomitus -f x.ind --emit=ast > out` should still colour
+    /// errors on the terminal, and `2> log` shouldn't put escapes in the log.
+    pub fn stderr() -> Emitter {
+        Emitter::new(std::io::stderr().is_terminal())
+    }
+
+    pub fn emit(&self, map: &SourceMap, diagnostic: &Diagnostic) {
+        eprint!("{}", self.render(map, diagnostic));
+    }
+
+    pub fn emit_all(&self, map: &SourceMap, diagnostics: &[Diagnostic]) {
+        for diagnostic in diagnostics {
+            self.emit(map, diagnostic);
+        }
+    }
+
+    pub fn render(&self, map: &SourceMap, diagnostic: &Diagnostic) -> String {
+        let mut out = format!(
+            "{}{}\n",
+            self.paint("error", RED),
+            self.paint(&format!(": {}", diagnostic.message), BOLD),
+        );
+
+        let width = diagnostic.labels.iter()
+            .map(|label| map.get(label.span.file).line_col(label.span.start).0)
+            .max()
+            .unwrap_or(0)
+            .to_string()
+            .len();
+        let pad = " ".repeat(width);
+        let gutter = self.paint("|", BLUE);
+
+        for (i, file) in self.files(diagnostic).into_iter().enumerate() {
+            let source = map.get(file);
+            let mut labels: Vec<&Label> = diagnostic.labels.iter().filter(|label| label.span.file == file).collect();
+            labels.sort_by_key(|label| (label.span.start, !label.primary));
+
+            let first = diagnostic.primary_span()
+                .filter(|span| span.file == file)
+                .unwrap_or(labels[0].span);
+            let (line, col) = source.line_col(first.start);
+            let arrow = if i == 0 { "-->" } else { ":::" };
+            out += &format!("{pad}{} {}:{}:{}\n", self.paint(arrow, BLUE), source.name, line, col);
+            out += &format!("{pad} {gutter}\n");
+
+            let mut previous: Option<usize> = None;
+            for label in &labels {
+                let (line, _) = source.line_col(label.span.start);
+                let text = source.line_text(line);
+
+                if previous != Some(line) {
+                    if previous.is_some_and(|previous| line > previous + 1) {
+                        out += &format!("{}\n", self.paint("...", BLUE));
+                    }
+                    out += &format!("{} {gutter} {text}\n", self.paint(&format!("{line:>width$}"), BLUE));
+                    previous = Some(line);
+                }
+
+                let offset = label.span.start - source.line_start(line);
+                out += &format!("{pad} {gutter} {}\n", self.underline(text, offset, label));
+            }
+        }
+
+        if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+            if !diagnostic.labels.is_empty() {
+                out += &format!("{pad} {gutter}\n");
+            }
+            for note in &diagnostic.notes {
+                out += &format!("{pad} {} {}: {note}\n", self.paint("=", BLUE), self.paint("note", BOLD));
+            }
+            for help in &diagnostic.help {
+                out += &format!("{pad} {} {}: {help}\n", self.paint("=", BLUE), self.paint("help", CYAN));
+            }
+        }
+
+        out + "\n"
+    }
+
+    /// Returns the files referenced by a diagnostic, the primary one first.
+    fn files(&self, diagnostic: &Diagnostic) -> Vec<FileId> {
+        let mut files: Vec<FileId> = Vec::new();
+        if let Some(span) = diagnostic.primary_span() {
+            files.push(span.file);
+        }
+        for label in &diagnostic.labels {
+            if !files.contains(&label.span.file) {
+                files.push(label.span.file);
+            }
+        }
+        files
+    }
+
+    /// Underlines a label whose span starts `offset` bytes into the line `text`.
+    fn underline(&self, text: &str, offset: usize, label: &Label) -> String {
+        let start = offset.min(text.len());
+        let indent: String = text[..start].chars()
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let end = (start + label.span.len()).min(text.len());
+        let length = text[start..end].chars().count().max(1);
+
+        let (marker, style) = if label.primary { ("^", RED) } else { ("-", BLUE) };
+        let mut underline = marker.repeat(length);
+        if !label.message.is_empty() {
+            underline = format!("{underline} {}", label.message);
+        }
+
+        format!("{indent}{}", self.paint(&underline, style))
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[test]
+fn render_test() {
+    use crate::source::Span;
+
+    let mut map = SourceMap::new();
+    let file = map.add_file(String::from("test.ind"), String::from("const x = 5;\nx = 7\nvar y = x;\n"));
+
+    let diagnostic = Diagnostic::error("cannot assign twice to constant `x`")
+        .with_label(Label::primary(Span::new(file, 13, 18, 2, 1), "cannot assign twice"))
+        .with_label(Label::secondary(Span::new(file, 6, 7, 1, 7), "first assignment"))
+        .with_note("`x` was declared with `const`")
+        .with_help("declare `x` with `var` instead");
+
+    let expected = "\
+error: cannot assign twice to constant `x`
+ --> test.ind:2:1
+  |
+1 | const x = 5;
+  |       - first assignment
+2 | x = 7
+  | ^^^^^ cannot assign twice
+  |
+  = note: `x` was declared with `const`
+  = help: declare `x` with `var` instead
+
+";
+
+    assert_eq!(Emitter::new(false).render(&map, &diagnostic), expected);
+}
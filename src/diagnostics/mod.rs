@@ -0,0 +1,5 @@
+pub mod diagnostic;
+pub mod emitter;
+
+pub use diagnostic::{ Diagnostic, Label, ToDiagnostic };
+pub use emitter::Emitter;
@@ -0,0 +1,347 @@
+use std::fmt::Display;
+
+use crate::lexer::NumSuffix;
+use crate::parser::ast;
+use crate::source::Span;
+
+/// A minimal JSON document model used by `--emit=ast-json`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl Json {
+    /// Starts an object describing an AST node of the given kind.
+    pub fn node(kind: &str, span: Span) -> Json {
+        Json::Object(vec![
+            (String::from("kind"), Json::String(kind.to_string())),
+            (String::from("span"), span.to_json()),
+        ])
+    }
+
+    pub fn with(mut self, key: &str, value: Json) -> Json {
+        if let Json::Object(fields) = &mut self {
+            fields.push((key.to_string(), value));
+        }
+        self
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        let pad = "  ".repeat(indent + 1);
+        let end = "  ".repeat(indent);
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) => write!(f, "{value}"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(items) if items.is_empty() => write!(f, "[]"),
+            Json::Array(items) => {
+                writeln!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(f, "{pad}")?;
+                    item.write(f, indent + 1)?;
+                    writeln!(f, "{}", if i + 1 < items.len() { "," } else { "" })?;
+                }
+                write!(f, "{end}]")
+            },
+            Json::Object(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Json::Object(fields) => {
+                writeln!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write!(f, "{pad}")?;
+                    write_string(f, key)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, "{}", if i + 1 < fields.len() { "," } else { "" })?;
+                }
+                write!(f, "{end}}}")
+            },
+        }
+    }
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, value: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for ch in value.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => write!(f, "{ch}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl ToJson for Span {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            (String::from("file"), Json::Number(self.file.0.to_string())),
+            (String::from("start"), Json::Number(self.start.to_string())),
+            (String::from("end"), Json::Number(self.end.to_string())),
+            (String::from("line"), Json::Number(self.line.to_string())),
+            (String::from("col"), Json::Number(self.col.to_string())),
+        ])
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(|item| item.to_json()).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        return match self {
+            Some(item) => item.to_json(),
+            None => Json::Null,
+        };
+    }
+}
+
+impl<T: ToJson> ToJson for Box<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().to_json()
+    }
+}
+
+impl ToJson for ast::Program {
+    fn to_json(&self) -> Json {
+        Json::node("Program", self.span)
+            .with("statements", self.statements.to_json())
+    }
+}
+
+impl ToJson for ast::Statement {
+    fn to_json(&self) -> Json {
+        return match self {
+            ast::Statement::Let(stmt) => stmt.to_json(),
+            ast::Statement::Return(stmt) => stmt.to_json(),
+            ast::Statement::Expression(expr) => Json::node("ExpressionStatement", expr.span())
+                .with("expression", expr.to_json()),
+            ast::Statement::While(stmt) => Json::node("WhileStatement", stmt.span)
+                .with("condition", stmt.condition.to_json())
+                .with("body", stmt.body.to_json()),
+            ast::Statement::For(stmt) => Json::node("ForStatement", stmt.span)
+                .with("variable", stmt.variable.to_json())
+                .with("iterable", stmt.iterable.to_json())
+                .with("body", stmt.body.to_json()),
+            ast::Statement::Break(stmt) => Json::node("BreakStatement", stmt.span),
+            ast::Statement::Continue(stmt) => Json::node("ContinueStatement", stmt.span),
+            ast::Statement::Assign(stmt) => {
+                let operator = match stmt.operator {
+                    Some(operator) => Json::String(operator.to_string()),
+                    None => Json::Null,
+                };
+                Json::node("AssignStatement", stmt.span)
+                    .with("target", stmt.target.to_json())
+                    .with("operator", operator)
+                    .with("value", stmt.value.to_json())
+            },
+        };
+    }
+}
+
+impl ToJson for ast::LetStatement {
+    fn to_json(&self) -> Json {
+        Json::node("LetStatement", self.span)
+            .with("docs", Json::Array(self.docs.iter().map(|doc| Json::String(doc.clone())).collect()))
+            .with("modifier", Json::String(self.modifier.to_string()))
+            .with("name", self.name.to_json())
+            .with("type", self.type_annotation.to_json())
+            .with("value", self.value.to_json())
+    }
+}
+
+impl ToJson for ast::ReturnStatement {
+    fn to_json(&self) -> Json {
+        Json::node("ReturnStatement", self.span)
+            .with("value", self.return_value.to_json())
+    }
+}
+
+impl ToJson for ast::Identifier {
+    fn to_json(&self) -> Json {
+        Json::node("Identifier", self.span)
+            .with("name", Json::String(self.value.to_string()))
+    }
+}
+
+impl ToJson for ast::Expression {
+    fn to_json(&self) -> Json {
+        return match self {
+            ast::Expression::Identifier(expr) => expr.to_json(),
+            ast::Expression::Integer(expr) => Json::node("IntegerLiteral", expr.span)
+                .with("value", Json::Number(expr.value.to_string()))
+                .with("suffix", suffix_json(&expr.suffix)),
+            ast::Expression::Float(expr) => Json::node("FloatLiteral", expr.span)
+                .with("value", Json::Number(format!("{:?}", expr.value)))
+                .with("suffix", suffix_json(&expr.suffix)),
+            ast::Expression::Boolean(expr) => Json::node("BooleanLiteral", expr.span)
+                .with("value", Json::Bool(expr.value)),
+            ast::Expression::Str(expr) => Json::node("StringLiteral", expr.span)
+                .with("value", Json::String(expr.value.clone())),
+            ast::Expression::Null(expr) => Json::node("NullLiteral", expr.span),
+            ast::Expression::Prefix(expr) => Json::node("PrefixExpression", expr.span)
+                .with("operator", Json::String(expr.operator.to_string()))
+                .with("right", expr.right.to_json()),
+            ast::Expression::Infix(expr) => Json::node("InfixExpression", expr.span)
+                .with("operator", Json::String(expr.operator.to_string()))
+                .with("left", expr.left.to_json())
+                .with("right", expr.right.to_json()),
+            ast::Expression::Logical(expr) => Json::node("LogicalExpression", expr.span)
+                .with("operator", Json::String(expr.operator.to_string()))
+                .with("left", expr.left.to_json())
+                .with("right", expr.right.to_json()),
+            ast::Expression::Range(expr) => Json::node("RangeExpression", expr.span)
+                .with("start", expr.start.to_json())
+                .with("end", expr.end.to_json())
+                .with("inclusive", Json::Bool(expr.inclusive)),
+            ast::Expression::Function(expr) => expr.to_json(),
+            ast::Expression::Call(expr) => Json::node("CallExpression", expr.span)
+                .with("function", expr.function.to_json())
+                .with("arguments", expr.arguments.to_json()),
+            ast::Expression::Index(expr) => Json::node("IndexExpression", expr.span)
+                .with("object", expr.object.to_json())
+                .with("index", expr.index.to_json()),
+            ast::Expression::Field(expr) => Json::node("FieldExpression", expr.span)
+                .with("object", expr.object.to_json())
+                .with("field", expr.field.to_json()),
+            ast::Expression::If(expr) => Json::node("IfExpression", expr.span)
+                .with("condition", expr.condition.to_json())
+                .with("consequence", expr.consequence.to_json())
+                .with("alternative", expr.alternative.to_json()),
+            ast::Expression::Block(expr) => expr.to_json(),
+        };
+    }
+}
+
+impl ToJson for ast::BlockStatement {
+    fn to_json(&self) -> Json {
+        Json::node("BlockStatement", self.span)
+            .with("statements", self.statements.to_json())
+            .with("tail", self.tail.to_json())
+    }
+}
+
+impl ToJson for ast::Parameter {
+    fn to_json(&self) -> Json {
+        Json::node("Parameter", self.span)
+            .with("name", self.name.to_json())
+            .with("type", self.param_type.to_json())
+    }
+}
+
+impl ToJson for ast::FunctionLiteral {
+    fn to_json(&self) -> Json {
+        Json::node("FunctionLiteral", self.span)
+            .with("parameters", self.parameters.to_json())
+            .with("returnType", self.return_type.to_json())
+            .with("body", self.body.to_json())
+    }
+}
+
+impl ToJson for ast::TypeExpr {
+    fn to_json(&self) -> Json {
+        return match self {
+            ast::TypeExpr::Primitive(ty) => Json::node("PrimitiveType", ty.span)
+                .with("name", Json::String(ty.token.to_string())),
+            ast::TypeExpr::Optional(ty) => Json::node("OptionalType", ty.span)
+                .with("inner", ty.inner.to_json()),
+            ast::TypeExpr::Array(ty) => {
+                let length = match ty.length {
+                    Some(length) => Json::Number(length.to_string()),
+                    None => Json::Null,
+                };
+                Json::node("ArrayType", ty.span)
+                    .with("element", ty.element.to_json())
+                    .with("length", length)
+            },
+            ast::TypeExpr::Function(ty) => Json::node("FunctionType", ty.span)
+                .with("parameters", ty.parameters.to_json())
+                .with("returnType", ty.return_type.to_json()),
+        };
+    }
+}
+
+fn suffix_json(suffix: &Option<NumSuffix>) -> Json {
+    return match suffix {
+        Some(suffix) => Json::String(suffix.to_string()),
+        None => Json::Null,
+    };
+}
+
+#[test]
+fn ast_json_test() {
+    use crate::lexer::Lexer;
+    use crate::parser::parser::Parser;
+
+    let mut parser = Parser::new(Lexer::new("return -x;"));
+    let program = parser.parse_program();
+
+    let expected = r#"{
+  "kind": "Program",
+  "span": {
+    "file": 0,
+    "start": 0,
+    "end": 10,
+    "line": 1,
+    "col": 1
+  },
+  "statements": [
+    {
+      "kind": "ReturnStatement",
+      "span": {
+        "file": 0,
+        "start": 0,
+        "end": 10,
+        "line": 1,
+        "col": 1
+      },
+      "value": {
+        "kind": "PrefixExpression",
+        "span": {
+          "file": 0,
+          "start": 7,
+          "end": 9,
+          "line": 1,
+          "col": 8
+        },
+        "operator": "-",
+        "right": {
+          "kind": "Identifier",
+          "span": {
+            "file": 0,
+            "start": 8,
+            "end": 9,
+            "line": 1,
+            "col": 9
+          },
+          "name": "x"
+        }
+      }
+    }
+  ]
+}"#;
+
+    assert_eq!(program.to_json().to_string(), expected);
+}
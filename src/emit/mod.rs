@@ -0,0 +1,49 @@
+pub mod json;
+pub mod types;
+
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EmitStage {
+    Tokens,
+    Ast,
+    AstJson,
+    Types,
+    Ir,
+    Asm,
+}
+
+impl Display for EmitStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmitStage::Tokens => write!(f, "tokens"),
+            EmitStage::Ast => write!(f, "ast"),
+            EmitStage::AstJson => write!(f, "ast-json"),
+            EmitStage::Types => write!(f, "types"),
+            EmitStage::Ir => write!(f, "ir"),
+            EmitStage::Asm => write!(f, "asm"),
+        }
+    }
+}
+
+impl EmitStage {
+    pub const NAMES: [&'static str; 6] = ["tokens", "ast", "ast-json", "types", "ir", "asm"];
+
+    pub fn from_name(name: &str) -> Option<EmitStage> {
+        return match name {
+            "tokens" => Some(EmitStage::Tokens),
+            "ast" => Some(EmitStage::Ast),
+            "ast-json" => Some(EmitStage::AstJson),
+            "types" => Some(EmitStage::Types),
+            "ir" => Some(EmitStage::Ir),
+            "asm" => Some(EmitStage::Asm),
+            _ => None,
+        };
+    }
+
+    /// Whether the stage runs after parsing, and so requires a program
+    /// without syntax errors.
+    pub fn needs_program(&self) -> bool {
+        *self != EmitStage::Tokens
+    }
+}
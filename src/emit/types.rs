@@ -0,0 +1,38 @@
+use crate::semantic::resolver::{ DeclId, Resolution };
+use crate::semantic::type_checker::TypeTable;
+
+/// Lists every binding with its declared or inferred type, one per line in
+/// declaration order, for `--emit=types`.
+pub fn binding_types(resolution: &Resolution, types: &TypeTable) -> String {
+    let mut result = String::new();
+    for (id, declaration) in resolution.declarations.iter().enumerate() {
+        let ty = match types.binding_type(DeclId(id)) {
+            Some(ty) => ty.to_string(),
+            None => String::from("{unknown}"),
+        };
+        result = format!("{result}{}: {} {}: {ty}\n", declaration.span, declaration.kind, declaration.name);
+    }
+    result
+}
+
+#[test]
+fn binding_types_test() {
+    use crate::lexer::Lexer;
+    use crate::parser::parser::Parser;
+    use crate::semantic::resolver::Resolver;
+    use crate::semantic::type_checker::TypeChecker;
+
+    let input = "const x = 7;\nconst add = fn(a: i32) -> f32 { return 1.5; };\nfor i in 0..x { var y = 2.0; }\n";
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+    let (resolution, _) = Resolver::new().resolve_program(&program);
+    let (types, _) = TypeChecker::new(&resolution).check_program(&program);
+
+    assert_eq!(binding_types(&resolution, &types), "\
+1:7: const x: i32
+2:7: const add: fn(i32) -> f32
+2:16: parameter a: i32
+3:5: loop variable i: i32
+3:21: var y: f64
+");
+}
@@ -1,155 +1,599 @@
-use crate::lexer::Token;
+use crate::lexer::{ Token, SpannedToken, NumSuffix, Trivia, TriviaKind, LexError, LexErrorKind };
+use std::borrow::Cow;
 
-pub struct Lexer {
-    input: Vec<u8>,
+use unicode_xid::UnicodeXID;
+
+use crate::source::{ FileId, Span, Symbol, kw };
+
+/// Splits source text into tokens.
+///
+/// `pos` and `read_pos` are byte offsets into `input`, while `ch` is the whole
+/// character at `pos`, or `'\0'` once the input is exhausted. `col` counts
+/// characters rather than bytes.
+///
+/// Tokens borrow their text from `input`. As an `Iterator` the lexer yields
+/// every token up to and including `Eof`, then stops.
+///
+/// In lossless mode each token also carries the whitespace and comments that
+/// precede it, so the spans of the trivia and tokens together tile the whole
+/// input.
+///
+/// Lexical errors are collected in `errors`. Unexpected characters are
+/// skipped, while other malformed tokens become `Token::Error`.
+pub struct Lexer<'a> {
+    input: &'a str,
+    file: FileId,
     pos: usize,
     read_pos: usize,
-    ch: u8,
+    ch: char,
+    line: usize,
+    col: usize,
+    done: bool,
+    lossless: bool,
+    token_start: Span,
+    errors: Vec<LexError>,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Lexer {
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Lexer<'a> {
+        Lexer::for_file(FileId::default(), input)
+    }
+
+    pub fn for_file(file: FileId, input: &'a str) -> Lexer<'a> {
         let mut lexer = Lexer {
-            input: input.into_bytes(),
+            input,
+            file,
             pos: 0,
             read_pos: 0,
-            ch: 0,
+            ch: '\0',
+            line: 1,
+            col: 0,
+            done: false,
+            lossless: false,
+            token_start: Span::default(),
+            errors: Vec::new(),
         };
         lexer.read_char();
 
         return lexer;
     }
 
+    /// Makes the lexer attach whitespace and comments to tokens as trivia
+    /// instead of discarding them.
+    pub fn lossless(mut self) -> Lexer<'a> {
+        self.lossless = true;
+        self
+    }
+
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
+    }
+
     fn read_char(&mut self) {
-        if self.read_pos >= self.input.len() {
-            self.ch = 0;
+        if self.ch == '\n' {
+            self.line += 1;
+            self.col = 1;
         } else {
-            self.ch = self.input[self.read_pos];
+            self.col += 1;
         }
+
         self.pos = self.read_pos;
-        self.read_pos += 1;
+        match self.input[self.read_pos..].chars().next() {
+            Some(ch) => {
+                self.ch = ch;
+                self.read_pos += ch.len_utf8();
+            },
+            None => self.ch = '\0',
+        }
     }
 
-    pub fn next(&mut self) -> Token {
-        self.eat_whitespace();
+    /// Returns the next token, repeating `Eof` once the input is exhausted.
+    pub fn next_token(&mut self) -> SpannedToken<'a> {
+        let mut trivia = Vec::new();
+
+        loop {
+            let start = self.start_span();
+            self.token_start = start;
+            let kind = if self.ch.is_whitespace() {
+                self.eat_whitespace();
+                TriviaKind::Whitespace
+            } else if self.ch == '/' && matches!(self.peek(), '/' | '*') {
+                let kind = if self.peek() == '/' { TriviaKind::LineComment } else { TriviaKind::BlockComment };
+                if let Some(token) = self.read_comment() {
+                    return SpannedToken::with_trivia(token, self.end_span(start), trivia);
+                }
+                kind
+            } else {
+                match self.read_token() {
+                    Some(token) => return SpannedToken::with_trivia(token, self.end_span(start), trivia),
+                    None => TriviaKind::Skipped,
+                }
+            };
 
-        let tok: Token = match self.ch {
-            0 => Token::Eof,
-            b'=' => {
-                if self.peek() == b'=' {
+            if self.lossless {
+                trivia.push(Trivia::new(kind, self.end_span(start)));
+            }
+        }
+    }
+
+    fn start_span(&self) -> Span {
+        Span::new(self.file, self.pos, self.pos, self.line, self.col)
+    }
+
+    fn end_span(&self, start: Span) -> Span {
+        Span {
+            end: self.pos,
+            ..start
+        }
+    }
+
+    /// Records an error covering the token read so far, returning the token
+    /// that stands in for it.
+    fn error(&mut self, kind: LexErrorKind) -> Token<'a> {
+        self.errors.push(LexError::new(kind, self.end_span(self.token_start)));
+        Token::Error
+    }
+
+    /// Reads a token, or returns `None` after skipping an unexpected character.
+    fn read_token(&mut self) -> Option<Token<'a>> {
+        let tok: Token<'a> = match self.ch {
+            '\0' => return Some(Token::Eof),
+            '=' => {
+                if self.peek() == '=' {
                     self.read_char();
                     Token::Eq
                 } else {
                     Token::Assign
                 }
             },
-            b'+' => Token::Plus,
-            b'-' => {
-                if self.peek() == b'>' {
+            '+' => self.with_assign(Token::Plus, Token::PlusAssign),
+            '-' => {
+                if self.peek() == '>' {
                     self.read_char();
                     Token::ReturnOp
                 } else {
-                    Token::Dash
+                    self.with_assign(Token::Dash, Token::DashAssign)
                 }
             },
-            b'*' => Token::Asterisk,
-            b'/' => Token::Slash,
-            b'!' => {
-                if self.peek() == b'=' {
+            '*' => self.with_assign(Token::Asterisk, Token::AsteriskAssign),
+            '/' => self.with_assign(Token::Slash, Token::SlashAssign),
+            '%' => self.with_assign(Token::Percent, Token::PercentAssign),
+            '^' => self.with_assign(Token::Caret, Token::CaretAssign),
+            '!' => self.with_assign(Token::Bang, Token::NotEq),
+            '&' => {
+                if self.peek() == '&' {
                     self.read_char();
-                    Token::NotEq
+                    Token::And
                 } else {
-                    Token::Bang
+                    self.with_assign(Token::Ampersand, Token::AmpersandAssign)
                 }
             },
-            b'?' => Token::QMark,
-            b',' => Token::Comma,
-            b';' => Token::Semicolon,
-            b':' => Token::Colon,
-            b'(' => Token::LParen,
-            b')' => Token::RParen,
-            b'[' => Token::LBracket,
-            b']' => Token::RBracket,
-            b'{' => Token::LSquirly,
-            b'}' => Token::RSquirly,
-            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
-                let ident = self.read_ident();
-                return match ident.as_str() {
-                    "fn" => Token::Function,
-                    "var" => Token::Var,
-                    "const" => Token::Const,
-                    "return" => Token::Return,
-                    "if" => Token::If,
-                    "else" => Token::Else,
-                    "true" => Token::True,
-                    "false" => Token::False,
-                    "i8" => Token::I8,
-                    "i16" => Token::I16,
-                    "i32" => Token::I32,
-                    "i64" => Token::I64,
-                    "f8" => Token::F8,
-                    "f16" => Token::F16,
-                    "f32" => Token::F32,
-                    "f64" => Token::F64,
-                    "u8" => Token::U8,
-                    "bool" => Token::Bool,
-                    "null" => Token::Null,
-                    "String" => Token::StringTok,
-                    _ => Token::Ident(ident),
-                };
+            '|' => {
+                if self.peek() == '|' {
+                    self.read_char();
+                    Token::Or
+                } else {
+                    self.with_assign(Token::Pipe, Token::PipeAssign)
+                }
             },
-            b'0'..=b'9' => {
-                let num = self.read_num();
-                if num.contains(".") {
-                    return Token::Float(num)
+            '<' => {
+                if self.peek() == '<' {
+                    self.read_char();
+                    self.with_assign(Token::Shl, Token::ShlAssign)
                 } else {
-                    return Token::Int(num)
+                    self.with_assign(Token::Lt, Token::LtEq)
                 }
-            }
-            _ => Token::Illegal(self.ch),
+            },
+            '>' => {
+                if self.peek() == '>' {
+                    self.read_char();
+                    self.with_assign(Token::Shr, Token::ShrAssign)
+                } else {
+                    self.with_assign(Token::Gt, Token::GtEq)
+                }
+            },
+            '.' => {
+                if self.peek() == '.' {
+                    self.read_char();
+                    self.with_assign(Token::DotDot, Token::DotDotEq)
+                } else {
+                    Token::Dot
+                }
+            },
+            '?' => Token::QMark,
+            ',' => Token::Comma,
+            ';' => Token::Semicolon,
+            ':' => Token::Colon,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '{' => Token::LSquirly,
+            '}' => Token::RSquirly,
+            '"' => return Some(self.read_string()),
+            'r' if self.is_raw_string_start() => return Some(self.read_raw_string()),
+            ch if is_valid_var_start(ch) => {
+                let symbol = Symbol::intern(self.read_ident());
+                return Some(match symbol {
+                    kw::FN => Token::Function,
+                    kw::VAR => Token::Var,
+                    kw::CONST => Token::Const,
+                    kw::RETURN => Token::Return,
+                    kw::IF => Token::If,
+                    kw::ELSE => Token::Else,
+                    kw::TRUE => Token::True,
+                    kw::FALSE => Token::False,
+                    kw::WHILE => Token::While,
+                    kw::FOR => Token::For,
+                    kw::IN => Token::In,
+                    kw::BREAK => Token::Break,
+                    kw::CONTINUE => Token::Continue,
+                    kw::I8 => Token::I8,
+                    kw::I16 => Token::I16,
+                    kw::I32 => Token::I32,
+                    kw::I64 => Token::I64,
+                    kw::F8 => Token::F8,
+                    kw::F16 => Token::F16,
+                    kw::F32 => Token::F32,
+                    kw::F64 => Token::F64,
+                    kw::U8 => Token::U8,
+                    kw::BOOL => Token::Bool,
+                    kw::NULL => Token::Null,
+                    kw::STRING => Token::StringTok,
+                    _ => Token::Ident(symbol),
+                });
+            },
+            '0'..='9' => return Some(self.read_num()),
+            ch => {
+                self.read_char();
+                self.error(LexErrorKind::UnexpectedChar(ch));
+                return None;
+            },
         };
         
         self.read_char();
-        return tok;
+        return Some(tok);
+    }
+
+    /// Returns `assign` and consumes the `=` if one follows, otherwise `token`.
+    fn with_assign(&mut self, token: Token<'a>, assign: Token<'a>) -> Token<'a> {
+        if self.peek() == '=' {
+            self.read_char();
+            assign
+        } else {
+            token
+        }
     }
 
-    fn read_ident(&mut self) -> String {
+    fn read_ident(&mut self) -> &'a str {
         let start = self.pos;
         while is_valid_var_char(self.ch) {
             self.read_char();
         }
-        String::from_utf8_lossy(&self.input[start..self.pos]).to_string()
+        &self.input[start..self.pos]
     }
 
-    fn read_num(&mut self) -> String {
+    /// Reads an integer or float literal: `42`, `0xff`, `0b1010`, `0o17`,
+    /// `1_000`, `4.2`, `1e-9`, optionally followed by a type suffix such as
+    /// `42i64` or `3.0f32`.
+    fn read_num(&mut self) -> Token<'a> {
+        let radix = match (self.ch, self.peek()) {
+            ('0', 'x') => 16,
+            ('0', 'b') => 2,
+            ('0', 'o') => 8,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.read_char();
+            self.read_char();
+        }
+
         let start = self.pos;
-        while is_valid_num_char(self.ch) {
+        if let Err(reason) = self.read_digits(radix) {
+            return self.malformed_num(reason);
+        }
+        let mut is_float = false;
+
+        if radix == 10 && self.ch == '.' && self.peek().is_ascii_digit() {
             self.read_char();
+            if let Err(reason) = self.read_digits(10) {
+                return self.malformed_num(reason);
+            }
+            is_float = true;
+
+            if self.ch == '.' && self.peek().is_ascii_digit() {
+                return self.malformed_num(String::from("a number can only have one decimal point"));
+            }
+        }
+
+        if radix == 10 && matches!(self.ch, 'e' | 'E') {
+            self.read_char();
+            if matches!(self.ch, '+' | '-') {
+                self.read_char();
+            }
+            if self.read_digits(10).is_err() {
+                return self.malformed_num(String::from("expected digits after the exponent"));
+            }
+            is_float = true;
         }
-        String::from_utf8_lossy(&self.input[start..self.pos]).to_string()
+
+        //  only copy the literal when there are separators to strip
+        let text = match &self.input[start..self.pos] {
+            text if text.contains('_') => Cow::Owned(text.replace('_', "")),
+            text => Cow::Borrowed(text),
+        };
+
+        let mut suffix = None;
+        if is_valid_var_char(self.ch) {
+            let name = self.read_ident();
+            suffix = match NumSuffix::from_name(name) {
+                Some(suffix) => Some(suffix),
+                None => return self.malformed_num(format!("invalid suffix `{name}`")),
+            };
+        }
+
+        return match suffix {
+            Some(suffix) if is_float && !suffix.is_float() => {
+                self.malformed_num(format!("integer suffix `{suffix}` on a float literal"))
+            },
+            Some(suffix) if radix != 10 && suffix.is_float() => {
+                self.malformed_num(format!("float suffix `{suffix}` on a base {radix} literal"))
+            },
+            _ if is_float || suffix.is_some_and(|suffix| suffix.is_float()) => {
+                let value: f64 = text.parse().expect("is a valid float");
+                if value.is_finite() {
+                    Token::Float(value, suffix)
+                } else {
+                    self.malformed_num(String::from("float literal is too large"))
+                }
+            },
+            _ => match u64::from_str_radix(&text, radix) {
+                Ok(value) => Token::Int(value, suffix),
+                Err(_) => self.malformed_num(String::from("integer literal is too large")),
+            },
+        };
+    }
+
+    /// Skips over digits in the given base, checking that `_` separators only
+    /// appear between two digits.
+    fn read_digits(&mut self, radix: u32) -> Result<(), String> {
+        let mut digits = 0;
+        let mut after_separator = false;
+
+        loop {
+            let ch = self.ch;
+            if ch == '_' {
+                if digits == 0 || after_separator {
+                    return Err(String::from("`_` can only separate digits"));
+                }
+                after_separator = true;
+            } else if ch.is_digit(radix) {
+                digits += 1;
+                after_separator = false;
+            } else if ch.is_ascii_digit() {
+                return Err(format!("invalid digit `{ch}` in a base {radix} literal"));
+            } else {
+                break;
+            }
+            self.read_char();
+        }
+
+        if after_separator {
+            return Err(String::from("`_` can only separate digits"));
+        }
+        if digits == 0 {
+            return Err(format!("expected base {radix} digits"));
+        }
+
+        Ok(())
+    }
+
+    /// Skips the rest of a malformed number so it's reported as one token.
+    fn malformed_num(&mut self, reason: String) -> Token<'a> {
+        while is_valid_var_char(self.ch) || (self.ch == '.' && self.peek().is_ascii_digit()) {
+            self.read_char();
+        }
+        self.error(LexErrorKind::MalformedNumber(reason))
+    }
+
+    /// Reads a `"` delimited string, decoding any escape sequences.
+    ///
+    /// Strings can't span lines, use a raw string for that.
+    fn read_string(&mut self) -> Token<'a> {
+        //  the value is only copied out of the source once an escape is decoded
+        let mut value: Option<String> = None;
+        let mut invalid = false;
+        self.read_char();
+        let start = self.pos;
+        let end;
+
+        loop {
+            match self.ch {
+                '\0' | '\n' => return self.error(LexErrorKind::UnterminatedString),
+                '"' => {
+                    end = self.pos;
+                    self.read_char();
+                    break;
+                },
+                '\\' => {
+                    let escape_start = self.start_span();
+                    let value = value.get_or_insert_with(|| self.input[start..escape_start.start].to_string());
+                    self.read_char();
+                    let decoded = match self.ch {
+                        '\0' | '\n' => return self.error(LexErrorKind::UnterminatedString),
+                        'n' => Some('\n'),
+                        't' => Some('\t'),
+                        'r' => Some('\r'),
+                        '0' => Some('\0'),
+                        '\\' => Some('\\'),
+                        '"' => Some('"'),
+                        'u' => self.read_unicode_escape(),
+                        _ => None,
+                    };
+
+                    match decoded {
+                        Some(ch) => value.push(ch),
+                        None => {
+                            let span = Span { end: self.read_pos, ..escape_start };
+                            let escape = self.input[span.start..span.end].to_string();
+                            self.errors.push(LexError::new(LexErrorKind::InvalidEscape(escape), span));
+                            invalid = true;
+                        },
+                    }
+                    self.read_char();
+                },
+                ch => {
+                    if let Some(value) = &mut value {
+                        value.push(ch);
+                    }
+                    self.read_char();
+                },
+            }
+        }
+
+        return match (invalid, value) {
+            (true, _) => Token::Error,
+            (false, Some(value)) => Token::Str(Cow::Owned(value)),
+            (false, None) => Token::Str(Cow::Borrowed(&self.input[start..end])),
+        };
+    }
+
+    /// Reads the `{XXXX}` of a `\u{XXXX}` escape, leaving the lexer on the
+    /// closing brace.
+    fn read_unicode_escape(&mut self) -> Option<char> {
+        if self.peek() != '{' {
+            return None;
+        }
+        self.read_char();
+
+        let start = self.read_pos;
+        while self.peek().is_ascii_hexdigit() {
+            self.read_char();
+        }
+        let digits = &self.input[start..self.read_pos];
+
+        if self.peek() != '}' || digits.is_empty() || digits.len() > 6 {
+            return None;
+        }
+        self.read_char();
+
+        u32::from_str_radix(digits, 16).ok().and_then(char::from_u32)
+    }
+
+    /// Whether the lexer is at the `r` of a raw string, `r"..."` or `r#"..."#`.
+    fn is_raw_string_start(&self) -> bool {
+        self.input[self.read_pos..].trim_start_matches('#').starts_with('"')
+    }
+
+    /// Reads a raw string, which can span multiple lines and doesn't process
+    /// escapes. Any number of `#`s can surround the quotes so that the string
+    /// can contain `"#`.
+    fn read_raw_string(&mut self) -> Token<'a> {
+        self.read_char();
+        let mut hashes = 0;
+        while self.ch == '#' {
+            hashes += 1;
+            self.read_char();
+        }
+        self.read_char();
+
+        let start = self.pos;
+        loop {
+            match self.ch {
+                '\0' => return self.error(LexErrorKind::UnterminatedString),
+                '"' if self.input[self.read_pos..].starts_with(&"#".repeat(hashes)) => {
+                    let value = &self.input[start..self.pos];
+                    for _ in 0..=hashes {
+                        self.read_char();
+                    }
+                    return Token::Str(Cow::Borrowed(value));
+                },
+                _ => self.read_char(),
+            }
+        }
+    }
+
+    /// Reads a comment, returning a token only for doc comments.
+    fn read_comment(&mut self) -> Option<Token<'a>> {
+        self.read_char();
+
+        if self.ch == '/' {
+            self.read_char();
+            let is_doc = self.ch == '/' && self.peek() != '/';
+            if is_doc {
+                self.read_char();
+            }
+
+            let start = self.pos;
+            while self.ch != '\n' && self.ch != '\0' {
+                self.read_char();
+            }
+
+            if !is_doc {
+                return None;
+            }
+
+            let text = &self.input[start..self.pos];
+            let text = text.trim_end_matches('\r');
+            return Some(Token::DocComment(Cow::Borrowed(text.strip_prefix(' ').unwrap_or(text))));
+        }
+
+        //  block comments nest, so `/* /* */ */` is a single comment
+        self.read_char();
+        let mut depth = 1;
+        while depth > 0 {
+            match (self.ch, self.peek()) {
+                ('\0', _) => {
+                    self.error(LexErrorKind::UnterminatedComment);
+                    return None;
+                },
+                ('/', '*') => {
+                    depth += 1;
+                    self.read_char();
+                },
+                ('*', '/') => {
+                    depth -= 1;
+                    self.read_char();
+                },
+                _ => {},
+            }
+            self.read_char();
+        }
+
+        None
     }
 
     fn eat_whitespace(&mut self) {
-        while self.ch.is_ascii_whitespace() {
+        while self.ch.is_whitespace() {
             self.read_char();
         }
     }
 
-    fn peek(&self) -> u8 {
-        if self.read_pos >= self.input.len() {
-            0
-        } else {
-            self.input[self.read_pos]
+    fn peek(&self) -> char {
+        self.input[self.read_pos..].chars().next().unwrap_or('\0')
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = SpannedToken<'a>;
+
+    fn next(&mut self) -> Option<SpannedToken<'a>> {
+        if self.done {
+            return None;
         }
+
+        let token = self.next_token();
+        self.done = token.token == Token::Eof;
+        Some(token)
     }
 }
 
-fn is_valid_var_char(ch: u8) -> bool {
-    ch.is_ascii_alphanumeric() || ch == b'_'
+fn is_valid_var_start(ch: char) -> bool {
+    ch == '_' || ch.is_xid_start()
 }
 
-fn is_valid_num_char(ch: u8) -> bool {
-    ch.is_ascii_digit() || ch == b'.' || ch == b'_'
+fn is_valid_var_char(ch: char) -> bool {
+    ch.is_xid_continue()
 }
 
 #[test]
@@ -163,25 +607,25 @@ fn text_next_token() {
             x = y;
         }
         "#;
-    let mut lex = Lexer::new(input.into());
+    let mut lex = Lexer::new(input);
 
     let tokens = vec![
         Token::Const,
-        Token::Ident(String::from("x")),
+        Token::Ident("x".into()),
         Token::Colon,
         Token::I32,
         Token::Assign,
-        Token::Int(String::from("5")),
+        Token::Int(5, None),
         Token::Semicolon,
         Token::Const,
-        Token::Ident(String::from("y")),
+        Token::Ident("y".into()),
         Token::Colon,
         Token::F64,
         Token::Assign,
-        Token::Float(String::from("4.2")),
+        Token::Float(4.2, None),
         Token::Semicolon,
         Token::Var,
-        Token::Ident(String::from("z")),
+        Token::Ident("z".into()),
         Token::Colon,
         Token::QMark,
         Token::U8,
@@ -195,21 +639,318 @@ fn text_next_token() {
         Token::Colon,
         Token::If,
         Token::LParen,
-        Token::Ident(String::from("x")),
+        Token::Ident("x".into()),
         Token::NotEq,
-        Token::Ident(String::from("y")),
+        Token::Ident("y".into()),
         Token::RParen,
         Token::LSquirly,
-        Token::Ident(String::from("x")),
+        Token::Ident("x".into()),
         Token::Assign,
-        Token::Ident(String::from("y")),
+        Token::Ident("y".into()),
         Token::Semicolon,
         Token::RSquirly,
     ];
 
     for token in tokens {
-        let next_token = lex.next();
-        //println!("expected: {}, received: {}", token, next_token);
+        let next_token = lex.next_token().token;
         assert_eq!(token, next_token);
     }
 }
+
+#[test]
+fn test_token_spans() {
+    let input = "const x = 5;\n  x == 10";
+    let mut lex = Lexer::new(input);
+
+    let spans = vec![
+        (Token::Const, 0, 5, 1, 1),
+        (Token::Ident("x".into()), 6, 7, 1, 7),
+        (Token::Assign, 8, 9, 1, 9),
+        (Token::Int(5, None), 10, 11, 1, 11),
+        (Token::Semicolon, 11, 12, 1, 12),
+        (Token::Ident("x".into()), 15, 16, 2, 3),
+        (Token::Eq, 17, 19, 2, 5),
+        (Token::Int(10, None), 20, 22, 2, 8),
+        (Token::Eof, 22, 22, 2, 10),
+    ];
+
+    for (token, start, end, line, col) in spans {
+        let next_token = lex.next_token();
+        assert_eq!(next_token, SpannedToken::with_trivia(token, Span::new(FileId::default(), start, end, line, col), Vec::new()));
+    }
+}
+
+#[test]
+fn test_string_literals() {
+    let input = r###"
+        "hello" "tab\tquote\" slash\\ nl\n" "\u{48}\u{1F600}" ""
+        r"raw \n" r#"has "quotes""# r##"multi
+line"#"##
+        "bad \q escape" "bad \u{110000}" rest "unterminated
+        r#"never closed
+        "###;
+    let mut lex = Lexer::new(input);
+
+    let tokens = vec![
+        Token::Str("hello".into()),
+        Token::Str("tab\tquote\" slash\\ nl\n".into()),
+        Token::Str("H\u{1F600}".into()),
+        Token::Str("".into()),
+        Token::Str("raw \\n".into()),
+        Token::Str("has \"quotes\"".into()),
+        Token::Str("multi\nline\"#".into()),
+        Token::Error,
+        Token::Error,
+        Token::Ident("rest".into()),
+        Token::Error,
+        Token::Error,
+        Token::Eof,
+    ];
+
+    for token in tokens {
+        assert_eq!(token, lex.next_token().token);
+    }
+
+    let errors: Vec<String> = lex.errors().iter().map(|err| err.to_string()).collect();
+    assert_eq!(errors, vec![
+        "5:14: Invalid escape sequence: \\q",
+        "5:30: Invalid escape sequence: \\u{110000}",
+        "5:47: Unterminated string literal",
+        "6:9: Unterminated string literal",
+    ]);
+}
+
+#[test]
+fn test_comments() {
+    let input = r#"
+        // a line comment
+        const x = 1; // trailing
+        /* block /* nested */ still comment */ var
+        /// documents y
+        ///no space
+        //// not a doc comment
+        const y = x / 2;
+        /* never closed
+        "#;
+    let mut lex = Lexer::new(input);
+
+    let tokens = vec![
+        Token::Const,
+        Token::Ident("x".into()),
+        Token::Assign,
+        Token::Int(1, None),
+        Token::Semicolon,
+        Token::Var,
+        Token::DocComment("documents y".into()),
+        Token::DocComment("no space".into()),
+        Token::Const,
+        Token::Ident("y".into()),
+        Token::Assign,
+        Token::Ident("x".into()),
+        Token::Slash,
+        Token::Int(2, None),
+        Token::Semicolon,
+        Token::Eof,
+    ];
+
+    for token in tokens {
+        assert_eq!(token, lex.next_token().token);
+    }
+
+    assert_eq!(lex.errors().len(), 1);
+    assert_eq!(lex.errors()[0].kind, LexErrorKind::UnterminatedComment);
+    assert_eq!(lex.errors()[0].span.line, 9);
+}
+
+#[test]
+fn test_operators() {
+    let input = "< > <= >= && || % & | ^ << >> += -= *= /= %= &= |= ^= <<= >>= -> - ! != = == .. ..= 0..=9";
+    let mut lex = Lexer::new(input);
+
+    let tokens = vec![
+        Token::Lt,
+        Token::Gt,
+        Token::LtEq,
+        Token::GtEq,
+        Token::And,
+        Token::Or,
+        Token::Percent,
+        Token::Ampersand,
+        Token::Pipe,
+        Token::Caret,
+        Token::Shl,
+        Token::Shr,
+        Token::PlusAssign,
+        Token::DashAssign,
+        Token::AsteriskAssign,
+        Token::SlashAssign,
+        Token::PercentAssign,
+        Token::AmpersandAssign,
+        Token::PipeAssign,
+        Token::CaretAssign,
+        Token::ShlAssign,
+        Token::ShrAssign,
+        Token::ReturnOp,
+        Token::Dash,
+        Token::Bang,
+        Token::NotEq,
+        Token::Assign,
+        Token::Eq,
+        Token::DotDot,
+        Token::DotDotEq,
+        Token::Int(0, None),
+        Token::DotDotEq,
+        Token::Int(9, None),
+        Token::Eof,
+    ];
+
+    for token in tokens {
+        assert_eq!(token, lex.next_token().token);
+    }
+}
+
+#[test]
+fn test_numbers() {
+    let input = "0 1_000 0xff 0xFF_FFi64 0b1010 0o17 4.2 1e-9 2.5E+3 42i64 3.0f32 7f64 255u8 1.foo 1..";
+    let mut lex = Lexer::new(input);
+
+    let tokens = vec![
+        Token::Int(0, None),
+        Token::Int(1000, None),
+        Token::Int(255, None),
+        Token::Int(0xffff, Some(NumSuffix::I64)),
+        Token::Int(10, None),
+        Token::Int(15, None),
+        Token::Float(4.2, None),
+        Token::Float(1e-9, None),
+        Token::Float(2500.0, None),
+        Token::Int(42, Some(NumSuffix::I64)),
+        Token::Float(3.0, Some(NumSuffix::F32)),
+        Token::Float(7.0, Some(NumSuffix::F64)),
+        Token::Int(255, Some(NumSuffix::U8)),
+        Token::Int(1, None),
+        Token::Dot,
+        Token::Ident("foo".into()),
+        Token::Int(1, None),
+        Token::DotDot,
+        Token::Eof,
+    ];
+
+    for token in tokens {
+        assert_eq!(token, lex.next_token().token);
+    }
+
+    let malformed = vec![
+        ("1.2.3", "a number can only have one decimal point"),
+        ("1__0", "`_` can only separate digits"),
+        ("1_", "`_` can only separate digits"),
+        ("0x_1", "`_` can only separate digits"),
+        ("0b102", "invalid digit `2` in a base 2 literal"),
+        ("0o8", "invalid digit `8` in a base 8 literal"),
+        ("0x", "expected base 16 digits"),
+        ("1e", "expected digits after the exponent"),
+        ("1e+x", "expected digits after the exponent"),
+        ("12abc", "invalid suffix `abc`"),
+        ("1.5i32", "integer suffix `i32` on a float literal"),
+        ("0x1g", "invalid suffix `g`"),
+        ("0b1f32", "float suffix `f32` on a base 2 literal"),
+        ("18446744073709551616", "integer literal is too large"),
+        ("1e999", "float literal is too large"),
+    ];
+
+    for (input, reason) in malformed {
+        let mut lex = Lexer::new(input);
+        let token = lex.next_token();
+        assert_eq!(token.token, Token::Error, "{input}");
+        assert_eq!(lex.next_token().token, Token::Eof, "{input}");
+        assert_eq!(lex.errors(), &[LexError::new(LexErrorKind::MalformedNumber(String::from(reason)), token.span)], "{input}");
+    }
+}
+
+#[test]
+fn test_unicode() {
+    let input = "const café = \"naïve\";\nπ_2 ≠ 😀 Δx";
+    let mut lex = Lexer::new(input);
+
+    let spans = vec![
+        (Token::Const, 0, 5, 1, 1),
+        (Token::Ident("café".into()), 6, 11, 1, 7),
+        (Token::Assign, 12, 13, 1, 12),
+        (Token::Str("naïve".into()), 14, 22, 1, 14),
+        (Token::Semicolon, 22, 23, 1, 21),
+        (Token::Ident("π_2".into()), 24, 28, 2, 1),
+        (Token::Ident("Δx".into()), 38, 41, 2, 9),
+        (Token::Eof, 41, 41, 2, 11),
+    ];
+
+    for (token, start, end, line, col) in spans {
+        let next_token = lex.next_token();
+        assert_eq!(next_token, SpannedToken::with_trivia(token, Span::new(FileId::default(), start, end, line, col), Vec::new()));
+    }
+
+    assert_eq!(lex.errors(), &[
+        LexError::new(LexErrorKind::UnexpectedChar('≠'), Span::new(FileId::default(), 29, 32, 2, 5)),
+        LexError::new(LexErrorKind::UnexpectedChar('😀'), Span::new(FileId::default(), 33, 37, 2, 7)),
+    ]);
+}
+
+#[test]
+fn test_token_iterator() {
+    let input = "const total = sum(1_000, \"a\\tb\") /// docs\n\"plain\"";
+    let tokens: Vec<Token> = Lexer::new(input).map(|token| token.token).collect();
+
+    assert_eq!(tokens, vec![
+        Token::Const,
+        Token::Ident("total".into()),
+        Token::Assign,
+        Token::Ident("sum".into()),
+        Token::LParen,
+        Token::Int(1000, None),
+        Token::Comma,
+        Token::Str("a\tb".into()),
+        Token::RParen,
+        Token::DocComment("docs".into()),
+        Token::Str("plain".into()),
+        Token::Eof,
+    ]);
+
+    //  text without escapes is borrowed straight from the source
+    for token in &tokens {
+        match token {
+            Token::DocComment(text) => assert!(matches!(text, Cow::Borrowed(_))),
+            Token::Str(text) if text == "plain" => assert!(matches!(text, Cow::Borrowed(_))),
+            Token::Str(text) => assert!(matches!(text, Cow::Owned(_))),
+            _ => {},
+        }
+    }
+
+    let mut lex = Lexer::new("x");
+    assert_eq!(lex.next_token().token, Token::Ident("x".into()));
+    assert_eq!(lex.next_token().token, Token::Eof);
+    assert_eq!(lex.next_token().token, Token::Eof);
+    assert_eq!(Lexer::new("").count(), 1);
+}
+
+#[test]
+fn test_lossless() {
+    let input = "/// adds\nconst add = fn(x: i32) -> i32 {\n\treturn x + 1; // one\n} /* a /* nested */ block */\n\n  \"s\\n\" r#\"raw\"# 0x_1 😀 \"open\n";
+
+    let tokens: Vec<SpannedToken> = Lexer::new(input).lossless().collect();
+    let mut text = String::new();
+    for token in &tokens {
+        for trivia in &token.trivia {
+            text += &input[trivia.span.start..trivia.span.end];
+        }
+        text += &input[token.span.start..token.span.end];
+    }
+    assert_eq!(text, input);
+
+    let kinds: Vec<TriviaKind> = tokens.iter()
+        .flat_map(|token| token.trivia.iter().map(|trivia| trivia.kind))
+        .filter(|kind| *kind != TriviaKind::Whitespace)
+        .collect();
+    assert_eq!(kinds, vec![TriviaKind::LineComment, TriviaKind::BlockComment, TriviaKind::Skipped]);
+
+    //  the default mode still drops trivia
+    assert!(Lexer::new(input).all(|token| token.trivia.is_empty()));
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::fmt::{ Display, Formatter };
+
+use crate::diagnostics::{ Diagnostic, Label, ToDiagnostic };
+use crate::source::Span;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexErrorKind {
+    UnexpectedChar(char),
+    UnterminatedString,
+    MalformedNumber(String),
+    InvalidEscape(String),
+    UnterminatedComment,
+}
+
+impl Display for LexErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexErrorKind::UnexpectedChar(ch) => write!(f, "Unexpected character {:?}", ch),
+            LexErrorKind::UnterminatedString => write!(f, "Unterminated string literal"),
+            LexErrorKind::MalformedNumber(reason) => write!(f, "Malformed number: {}", reason),
+            LexErrorKind::InvalidEscape(escape) => write!(f, "Invalid escape sequence: {}", escape),
+            LexErrorKind::UnterminatedComment => write!(f, "Unterminated block comment"),
+        }
+    }
+}
+
+/// A problem found while lexing. The lexer records these and carries on, so
+/// they're reported together with the parser's errors.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub span: Span,
+}
+
+impl Error for LexError {
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.span, self.kind)
+    }
+}
+
+impl LexError {
+    pub fn new(kind: LexErrorKind, span: Span) -> LexError {
+        LexError {
+            kind,
+            span,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        return match self.kind {
+            LexErrorKind::UnexpectedChar(_) => "unexpected character",
+            LexErrorKind::UnterminatedString => "string is never closed",
+            LexErrorKind::MalformedNumber(_) => "invalid literal",
+            LexErrorKind::InvalidEscape(_) => "invalid escape",
+            LexErrorKind::UnterminatedComment => "comment is never closed",
+        };
+    }
+}
+
+impl ToDiagnostic for LexError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.kind.to_string())
+            .with_label(Label::primary(self.span, self.label()));
+
+        return match self.kind {
+            LexErrorKind::UnexpectedChar(ch) if !ch.is_ascii() => {
+                diagnostic.with_note(format!("{:?} is the code point U+{:04X}", ch, ch as u32))
+            },
+            LexErrorKind::UnterminatedString => diagnostic.with_help("close the string with `\"`, or use a raw string `r\"...\"` to span multiple lines"),
+            LexErrorKind::InvalidEscape(_) => diagnostic.with_note("valid escapes are \\n \\t \\r \\0 \\\\ \\\" and \\u{...}"),
+            LexErrorKind::UnterminatedComment => diagnostic.with_note("block comments nest, each `/*` needs a matching `*/`"),
+            _ => diagnostic,
+        };
+    }
+}
+
+#[test]
+fn lex_error_diagnostic_test() {
+    use crate::diagnostics::Emitter;
+    use crate::lexer::Lexer;
+    use crate::source::SourceMap;
+
+    let mut map = SourceMap::new();
+    let file = map.add_file(String::from("main.ind"), String::from("const s = \"never closed;\n"));
+    let mut lexer = Lexer::for_file(file, &map.get(file).src);
+    lexer.by_ref().for_each(drop);
+
+    let [error] = lexer.errors() else { panic!("Expected one error, got {:?}", lexer.errors()) };
+    assert_eq!(error.kind, LexErrorKind::UnterminatedString);
+    assert_eq!((error.span.line, error.span.col, error.span.start), (1, 11, 10));
+
+    let diagnostic = error.to_diagnostic();
+    assert_eq!(diagnostic.primary_span(), Some(error.span));
+    assert_eq!(Emitter::new(false).render(&map, &diagnostic), "\
+error: Unterminated string literal
+ --> main.ind:1:11
+  |
+1 | const s = \"never closed;
+  |           ^^^^^^^^^^^^^^ string is never closed
+  |
+  = help: close the string with `\"`, or use a raw string `r\"...\"` to span multiple lines
+
+");
+}
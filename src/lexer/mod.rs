@@ -1,5 +1,7 @@
 pub mod token;
 pub mod lexer;
+pub mod lexer_errors;
 
-pub use token::Token;
+pub use token::{ Token, SpannedToken, NumSuffix, Trivia, TriviaKind };
 pub use lexer::Lexer;
+pub use lexer_errors::{ LexError, LexErrorKind };
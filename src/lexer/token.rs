@@ -1,14 +1,23 @@
+use std::borrow::Cow;
 use std::fmt::Display;
 
+use crate::source::{ Span, Symbol };
+
+/// A token, borrowing its text from the source it was lexed from wherever
+/// possible. Strings only own their value when escapes had to be decoded, and
+/// identifiers are interned.
 #[derive(Debug, PartialEq, Clone)]
-pub enum Token {
-    Illegal(u8),
+pub enum Token<'a> {
+    /// A malformed token, which the lexer has already reported.
+    Error,
     Eof,
 
     //  identifiers + literals
-    Ident(String),
-    Int(String),
-    Float(String),
+    Ident(Symbol),
+    Int(u64, Option<NumSuffix>),
+    Float(f64, Option<NumSuffix>),
+    Str(Cow<'a, str>),
+    DocComment(Cow<'a, str>),
 
     //  operators
     Assign,
@@ -20,7 +29,34 @@ pub enum Token {
     QMark,
     Eq,
     NotEq,
+    Lt,
+    Gt,
+    LtEq,
+    GtEq,
+    And,
+    Or,
+    Percent,
+    Ampersand,
+    Pipe,
+    Caret,
+    Shl,
+    Shr,
     ReturnOp,
+    Dot,
+    DotDot,
+    DotDotEq,
+
+    //  compound assignment
+    PlusAssign,
+    DashAssign,
+    AsteriskAssign,
+    SlashAssign,
+    PercentAssign,
+    AmpersandAssign,
+    PipeAssign,
+    CaretAssign,
+    ShlAssign,
+    ShrAssign,
 
     //  delimiters
     Comma,
@@ -43,6 +79,11 @@ pub enum Token {
     Else,
     True,
     False,
+    While,
+    For,
+    In,
+    Break,
+    Continue,
 
     //  types
     Null,
@@ -59,14 +100,100 @@ pub enum Token {
     StringTok,
 }
 
-impl Display for Token {
+impl Token<'_> {
+    /// Detaches the token from the source, for storing it past the lexer.
+    pub fn into_owned(self) -> Token<'static> {
+        return match self {
+            Token::Ident(symbol) => Token::Ident(symbol),
+            Token::Str(string) => Token::Str(Cow::Owned(string.into_owned())),
+            Token::DocComment(text) => Token::DocComment(Cow::Owned(text.into_owned())),
+            Token::Error => Token::Error,
+            Token::Eof => Token::Eof,
+            Token::Int(int, suffix) => Token::Int(int, suffix),
+            Token::Float(float, suffix) => Token::Float(float, suffix),
+            Token::Assign => Token::Assign,
+            Token::Plus => Token::Plus,
+            Token::Dash => Token::Dash,
+            Token::Asterisk => Token::Asterisk,
+            Token::Slash => Token::Slash,
+            Token::Bang => Token::Bang,
+            Token::QMark => Token::QMark,
+            Token::Eq => Token::Eq,
+            Token::NotEq => Token::NotEq,
+            Token::Lt => Token::Lt,
+            Token::Gt => Token::Gt,
+            Token::LtEq => Token::LtEq,
+            Token::GtEq => Token::GtEq,
+            Token::And => Token::And,
+            Token::Or => Token::Or,
+            Token::Percent => Token::Percent,
+            Token::Ampersand => Token::Ampersand,
+            Token::Pipe => Token::Pipe,
+            Token::Caret => Token::Caret,
+            Token::Shl => Token::Shl,
+            Token::Shr => Token::Shr,
+            Token::ReturnOp => Token::ReturnOp,
+            Token::Dot => Token::Dot,
+            Token::DotDot => Token::DotDot,
+            Token::DotDotEq => Token::DotDotEq,
+            Token::PlusAssign => Token::PlusAssign,
+            Token::DashAssign => Token::DashAssign,
+            Token::AsteriskAssign => Token::AsteriskAssign,
+            Token::SlashAssign => Token::SlashAssign,
+            Token::PercentAssign => Token::PercentAssign,
+            Token::AmpersandAssign => Token::AmpersandAssign,
+            Token::PipeAssign => Token::PipeAssign,
+            Token::CaretAssign => Token::CaretAssign,
+            Token::ShlAssign => Token::ShlAssign,
+            Token::ShrAssign => Token::ShrAssign,
+            Token::Comma => Token::Comma,
+            Token::Semicolon => Token::Semicolon,
+            Token::Colon => Token::Colon,
+            Token::LParen => Token::LParen,
+            Token::RParen => Token::RParen,
+            Token::LBracket => Token::LBracket,
+            Token::RBracket => Token::RBracket,
+            Token::LSquirly => Token::LSquirly,
+            Token::RSquirly => Token::RSquirly,
+            Token::Function => Token::Function,
+            Token::Var => Token::Var,
+            Token::Const => Token::Const,
+            Token::Return => Token::Return,
+            Token::If => Token::If,
+            Token::Else => Token::Else,
+            Token::True => Token::True,
+            Token::False => Token::False,
+            Token::While => Token::While,
+            Token::For => Token::For,
+            Token::In => Token::In,
+            Token::Break => Token::Break,
+            Token::Continue => Token::Continue,
+            Token::Null => Token::Null,
+            Token::Bool => Token::Bool,
+            Token::I8 => Token::I8,
+            Token::I16 => Token::I16,
+            Token::I32 => Token::I32,
+            Token::I64 => Token::I64,
+            Token::F8 => Token::F8,
+            Token::F16 => Token::F16,
+            Token::F32 => Token::F32,
+            Token::F64 => Token::F64,
+            Token::U8 => Token::U8,
+            Token::StringTok => Token::StringTok,
+        };
+    }
+}
+
+impl Display for Token<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         return match self {
-            Token::Illegal(tok) => write!(f, "Illegal({})", tok),
+            Token::Error => write!(f, "Error"),
             Token::Eof => write!(f, "Eof"),
             Token::Ident(ident) => write!(f, "Ident({})", ident),
-            Token::Int(int) => write!(f, "Int({})", int),
-            Token::Float(float) => write!(f, "Float({})", float),
+            Token::Int(int, suffix) => write!(f, "Int({}{})", int, NumSuffix::display(suffix)),
+            Token::Float(float, suffix) => write!(f, "Float({:?}{})", float, NumSuffix::display(suffix)),
+            Token::Str(string) => write!(f, "Str({:?})", string),
+            Token::DocComment(text) => write!(f, "Doc Comment({})", text),
             Token::Assign => write!(f, "Assign"),
             Token::Plus => write!(f, "Plus"),
             Token::Dash => write!(f, "Dash"),
@@ -76,7 +203,32 @@ impl Display for Token {
             Token::QMark => write!(f, "QMark"),
             Token::Eq => write!(f, "Eq"),
             Token::NotEq => write!(f, "Not Eq"),
+            Token::Lt => write!(f, "Lt"),
+            Token::Gt => write!(f, "Gt"),
+            Token::LtEq => write!(f, "Lt Eq"),
+            Token::GtEq => write!(f, "Gt Eq"),
+            Token::And => write!(f, "And"),
+            Token::Or => write!(f, "Or"),
+            Token::Percent => write!(f, "Percent"),
+            Token::Ampersand => write!(f, "Ampersand"),
+            Token::Pipe => write!(f, "Pipe"),
+            Token::Caret => write!(f, "Caret"),
+            Token::Shl => write!(f, "Shl"),
+            Token::Shr => write!(f, "Shr"),
             Token::ReturnOp => write!(f, "Return Op"),
+            Token::Dot => write!(f, "Dot"),
+            Token::DotDot => write!(f, "Dot Dot"),
+            Token::DotDotEq => write!(f, "Dot Dot Eq"),
+            Token::PlusAssign => write!(f, "Plus Assign"),
+            Token::DashAssign => write!(f, "Dash Assign"),
+            Token::AsteriskAssign => write!(f, "Asterisk Assign"),
+            Token::SlashAssign => write!(f, "Slash Assign"),
+            Token::PercentAssign => write!(f, "Percent Assign"),
+            Token::AmpersandAssign => write!(f, "Ampersand Assign"),
+            Token::PipeAssign => write!(f, "Pipe Assign"),
+            Token::CaretAssign => write!(f, "Caret Assign"),
+            Token::ShlAssign => write!(f, "Shl Assign"),
+            Token::ShrAssign => write!(f, "Shr Assign"),
             Token::Comma => write!(f, "Comma"),
             Token::Semicolon => write!(f, "Semicolon"),
             Token::Colon => write!(f, "Colon"),
@@ -94,6 +246,11 @@ impl Display for Token {
             Token::Else => write!(f, "else"),
             Token::True => write!(f, "true"),
             Token::False => write!(f, "false"),
+            Token::While => write!(f, "while"),
+            Token::For => write!(f, "for"),
+            Token::In => write!(f, "in"),
+            Token::Break => write!(f, "break"),
+            Token::Continue => write!(f, "continue"),
             Token::Null => write!(f, "null"),
             Token::Bool => write!(f, "bool"),
             Token::I8 => write!(f, "i8"),
@@ -109,3 +266,130 @@ impl Display for Token {
         }
     }
 }
+
+/// The type suffix of a numeric literal, as in `42i64` or `3.0f32`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NumSuffix {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    F8,
+    F16,
+    F32,
+    F64,
+}
+
+impl Display for NumSuffix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumSuffix::I8 => write!(f, "i8"),
+            NumSuffix::I16 => write!(f, "i16"),
+            NumSuffix::I32 => write!(f, "i32"),
+            NumSuffix::I64 => write!(f, "i64"),
+            NumSuffix::U8 => write!(f, "u8"),
+            NumSuffix::F8 => write!(f, "f8"),
+            NumSuffix::F16 => write!(f, "f16"),
+            NumSuffix::F32 => write!(f, "f32"),
+            NumSuffix::F64 => write!(f, "f64"),
+        }
+    }
+}
+
+impl NumSuffix {
+    pub fn from_name(name: &str) -> Option<NumSuffix> {
+        return match name {
+            "i8" => Some(NumSuffix::I8),
+            "i16" => Some(NumSuffix::I16),
+            "i32" => Some(NumSuffix::I32),
+            "i64" => Some(NumSuffix::I64),
+            "u8" => Some(NumSuffix::U8),
+            "f8" => Some(NumSuffix::F8),
+            "f16" => Some(NumSuffix::F16),
+            "f32" => Some(NumSuffix::F32),
+            "f64" => Some(NumSuffix::F64),
+            _ => None,
+        };
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumSuffix::F8 | NumSuffix::F16 | NumSuffix::F32 | NumSuffix::F64)
+    }
+
+    /// Formats an optional suffix, the empty string when there is none.
+    pub fn display(suffix: &Option<NumSuffix>) -> String {
+        return match suffix {
+            Some(suffix) => suffix.to_string(),
+            None => String::new(),
+        };
+    }
+}
+
+/// Whitespace or a comment, which carry no meaning but are needed to
+/// reproduce the source exactly.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TriviaKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    /// An unexpected character the lexer reported and skipped.
+    Skipped,
+}
+
+impl Display for TriviaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriviaKind::Whitespace => write!(f, "Whitespace"),
+            TriviaKind::LineComment => write!(f, "Line Comment"),
+            TriviaKind::BlockComment => write!(f, "Block Comment"),
+            TriviaKind::Skipped => write!(f, "Skipped"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+impl Display for Trivia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.kind, self.span)
+    }
+}
+
+impl Trivia {
+    pub fn new(kind: TriviaKind, span: Span) -> Trivia {
+        Trivia {
+            kind,
+            span,
+        }
+    }
+}
+
+/// A token and where it came from. `trivia` holds the whitespace and comments
+/// before the token, and is only filled in by a lossless lexer.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken<'a> {
+    pub token: Token<'a>,
+    pub span: Span,
+    pub trivia: Vec<Trivia>,
+}
+
+impl Display for SpannedToken<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.token, self.span)
+    }
+}
+
+impl<'a> SpannedToken<'a> {
+    pub fn with_trivia(token: Token<'a>, span: Span, trivia: Vec<Trivia>) -> SpannedToken<'a> {
+        SpannedToken {
+            token,
+            span,
+            trivia,
+        }
+    }
+}
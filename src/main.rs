@@ -1,15 +1,51 @@
+#![allow(clippy::needless_return, clippy::module_inception)]
+
+mod diagnostics;
+mod emit;
 mod lexer;
 mod parser;
+mod semantic;
+mod source;
+
+use std::io::{ Write, BufRead };
+use std::process::ExitCode;
+use clap::{ Command, Arg, ArgAction, error::ErrorKind };
 
-use std::io::{ Write, BufRead, BufReader };
-use std::fs::File;
-use clap::{ Command, Arg, ArgAction };
+use crate::diagnostics::{ Diagnostic, Emitter, ToDiagnostic };
+use crate::emit::{ EmitStage, json::ToJson };
+use crate::lexer::{ Lexer, LexError };
+use crate::parser::parser::Parser;
+use crate::semantic::mutability::MutabilityChecker;
+use crate::semantic::resolver::Resolver;
+use crate::semantic::type_checker::TypeChecker;
+use crate::source::SourceMap;
 
-use crate::lexer::{ Lexer, Token };
 
+fn main() -> ExitCode {
+    let matches = cli().get_matches();
+
+    match matches.subcommand() {
+        Some(("repl", _)) => {
+            repl().expect("Failed to run repl");
+            ExitCode::SUCCESS
+        },
+        Some(("file", file_matches)) => {
+            let path: &String = file_matches.get_one("path").expect("is present");
+            let emit = file_matches.get_one::<String>("emit")
+                .map(|name| EmitStage::from_name(name).expect("is a valid stage"));
+            let output: Option<&String> = file_matches.get_one("output");
+            let trivia = file_matches.get_flag("trivia");
+            if let Err(err) = check_trivia(emit, trivia) {
+                err.exit();
+            }
+            compile_file(path, emit, output, trivia)
+        },
+        _ => unreachable!(),
+    }
+}
 
-fn main() {
-    let matches = Command::new("indomitus")
+fn cli() -> Command {
+    Command::new("indomitus")
         .about("An experimental compiler")
         .version("0.4.0")
         .subcommand_required(true)
@@ -34,24 +70,48 @@ fn main() {
                 .action(ArgAction::Set)
                 .num_args(1)
             )
+            .arg(
+                Arg::new("emit")
+                .long("emit")
+                .help("the intermediate result to output")
+                .value_parser(EmitStage::NAMES)
+                .action(ArgAction::Set)
+                .num_args(1)
+            )
+            .arg(
+                Arg::new("trivia")
+                .long("trivia")
+                .help("with --emit=tokens, also list the whitespace and comments before each token")
+                .requires("emit")
+                .action(ArgAction::SetTrue)
+            )
+            .arg(
+                Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("the path to write the emitted output to, instead of stdout")
+                .requires("emit")
+                .action(ArgAction::Set)
+                .num_args(1)
+            )
         )
-        .get_matches();
+}
 
-    match matches.subcommand() {
-        Some(("repl", _)) => {
-            repl().expect("Failed to run repl");
-        },
-        Some(("file", file_matches)) => {
-            let path: &String = file_matches.get_one("path").expect("is present");
-            compile_file(path.to_string()).expect("Failed to copmile file");
-        },
-        _ => unreachable!(),
+/// Rejects `--trivia` with any stage but `tokens`, as only tokens carry it.
+fn check_trivia(emit: Option<EmitStage>, trivia: bool) -> Result<(), clap::Error> {
+    if trivia && emit != Some(EmitStage::Tokens) {
+        let mut cli = cli();
+        cli.build();
+        let file = cli.find_subcommand_mut("file").expect("is a subcommand");
+        return Err(file.error(ErrorKind::ArgumentConflict, "`--trivia` can only be used with `--emit=tokens`"));
     }
+    Ok(())
 }
 
 fn repl() -> std::io::Result<()> {
     let stdin = std::io::stdin();
     let mut stdin_lock = stdin.lock();
+    let emitter = Emitter::stderr();
     
     loop {
         print!(">> ");
@@ -64,39 +124,129 @@ fn repl() -> std::io::Result<()> {
             break;
         }
 
-        let mut lexer = Lexer::new(input);
-
-        loop {
-            let token = lexer.next();
+        //  each line is lexed on its own, as the only file in its map
+        let mut map = SourceMap::new();
+        let file = map.add_file(String::from("<repl>"), input);
+        let mut lexer = Lexer::new(&map.get(file).src);
+        for token in lexer.by_ref() {
             println!("{}", token);
-            if let Token::Eof = token {
-                break;
-            }
         }
+        let diagnostics: Vec<Diagnostic> = lexer.errors().iter().map(LexError::to_diagnostic).collect();
+        emitter.emit_all(&map, &diagnostics);
     }
 
     Ok(())
 }
 
-fn compile_file(file: String) -> std::io::Result<()> {
-    let file = File::open(file)?;
-    let reader = BufReader::new(file);
-
-    for line in reader.lines() {
-        match line {
-            Ok(line) => {
-                let mut lexer = Lexer::new(String::from(line));
-                loop {
-                    let token = lexer.next();
-                    println!("{}", token);
-                    if let Token::Eof = token {
-                        break;
-                    }
-                }
-            },
-            Err(err) => eprintln!("Error reading line: {}", err),
+fn compile_file(path: &str, emit: Option<EmitStage>, output: Option<&String>, trivia: bool) -> ExitCode {
+    let emitter = Emitter::stderr();
+    let mut map = SourceMap::new();
+
+    let src = match std::fs::read_to_string(path) {
+        Ok(src) => src,
+        Err(err) => {
+            emitter.emit(&map, &Diagnostic::error(format!("could not read `{path}`: {err}")));
+            return ExitCode::FAILURE;
+        },
+    };
+    let file = map.add_file(path.to_string(), src);
+    let src = &map.get(file).src;
+
+    if emit.is_some_and(|stage| !stage.needs_program()) {
+        let mut lexer = Lexer::for_file(file, src);
+        if trivia {
+            lexer = lexer.lossless();
         }
+        let tokens: String = lexer.by_ref()
+            .map(|token| {
+                let trivia: String = token.trivia.iter().map(|trivia| format!("  {trivia}\n")).collect();
+                format!("{trivia}{token}\n")
+            })
+            .collect();
+        //  the tokens, `Error` ones included, are still written, as they show
+        //  where lexing went wrong
+        let written = write_output(&emitter, &map, output, &tokens);
+        let diagnostics: Vec<Diagnostic> = lexer.errors().iter().map(LexError::to_diagnostic).collect();
+        if !diagnostics.is_empty() {
+            return report_errors(&emitter, &map, path, &diagnostics);
+        }
+        return written;
     }
 
-    Ok(())
+    let mut parser = Parser::new(Lexer::for_file(file, src));
+    let program = parser.parse_program();
+
+    let mut diagnostics: Vec<Diagnostic> = parser.lex_errors().iter().map(LexError::to_diagnostic).collect();
+    diagnostics.extend(parser.errors().to_diagnostics());
+    if !diagnostics.is_empty() {
+        return report_errors(&emitter, &map, path, &diagnostics);
+    }
+
+    match emit {
+        Some(EmitStage::Ast) => return write_output(&emitter, &map, output, &program.to_string()),
+        Some(EmitStage::AstJson) => return write_output(&emitter, &map, output, &format!("{}\n", program.to_json())),
+        _ => {},
+    }
+
+    let (resolution, errors) = Resolver::new().resolve_program(&program);
+    let mut diagnostics = errors.to_diagnostics();
+    diagnostics.extend(MutabilityChecker::new(&resolution).check_program(&program).to_diagnostics());
+    let (types, errors) = TypeChecker::new(&resolution).check_program(&program);
+    diagnostics.extend(errors.to_diagnostics());
+    if !diagnostics.is_empty() {
+        return report_errors(&emitter, &map, path, &diagnostics);
+    }
+
+    return match emit {
+        Some(EmitStage::Types) => write_output(&emitter, &map, output, &emit::types::binding_types(&resolution, &types)),
+        Some(stage @ (EmitStage::Ir | EmitStage::Asm)) => {
+            emitter.emit(&map, &Diagnostic::error(format!("`--emit={stage}` is not supported yet"))
+                .with_note("the compiler does not lower past type checking"));
+            ExitCode::FAILURE
+        },
+        Some(EmitStage::Tokens | EmitStage::Ast | EmitStage::AstJson) | None => ExitCode::SUCCESS,
+    };
+}
+
+/// Emits every diagnostic in source order and fails the compilation.
+fn report_errors(emitter: &Emitter, map: &SourceMap, path: &str, diagnostics: &[Diagnostic]) -> ExitCode {
+    let mut diagnostics = diagnostics.to_vec();
+    diagnostics.sort_by_key(|diagnostic| diagnostic.primary_span().map(|span| span.start));
+
+    emitter.emit_all(map, &diagnostics);
+    eprintln!("error: could not compile `{path}` due to {} previous error(s)", diagnostics.len());
+    ExitCode::FAILURE
+}
+
+fn write_output(emitter: &Emitter, map: &SourceMap, output: Option<&String>, text: &str) -> ExitCode {
+    let result = match output {
+        Some(path) => std::fs::write(path, text),
+        None => std::io::stdout().write_all(text.as_bytes()),
+    };
+
+    return match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            let target = output.map(String::as_str).unwrap_or("stdout");
+            emitter.emit(map, &Diagnostic::error(format!("could not write to `{target}`: {err}")));
+            ExitCode::FAILURE
+        },
+    };
+}
+
+#[test]
+fn cli_test() {
+    let matches = cli().try_get_matches_from(["indomitus", "file", "test.ind", "--emit=ast", "-o", "out.txt"]).unwrap();
+    let (_, file_matches) = matches.subcommand().unwrap();
+    assert_eq!(file_matches.get_one::<String>("output").map(String::as_str), Some("out.txt"));
+
+    let err = cli().try_get_matches_from(["indomitus", "file", "test.ind", "-o", "out.txt"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+}
+
+#[test]
+fn trivia_test() {
+    assert!(check_trivia(Some(EmitStage::Tokens), true).is_ok());
+    assert!(check_trivia(Some(EmitStage::Ast), false).is_ok());
+    assert_eq!(check_trivia(Some(EmitStage::Ast), true).unwrap_err().kind(), ErrorKind::ArgumentConflict);
 }
@@ -1,16 +1,19 @@
 use std::fmt::Display;
 
-use crate::lexer::Token;
+use crate::lexer::{ Token, NumSuffix };
+use crate::source::{ Span, Symbol };
 
 #[derive(Debug)]
 pub struct Program {
     pub statements: Vec<Statement>,
+    pub span: Span,
 }
 
 impl Program {
-    pub fn new(statements: Vec<Statement>) -> Program {
+    pub fn new(statements: Vec<Statement>, span: Span) -> Program {
         Program {
             statements,
+            span,
         }
     }
 }
@@ -30,6 +33,11 @@ pub enum Statement {
     Let(LetStatement),
     Return(ReturnStatement),
     Expression(Expression),
+    While(WhileStatement),
+    For(ForStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
+    Assign(AssignStatement),
 }
 
 impl Display for Statement {
@@ -37,36 +45,53 @@ impl Display for Statement {
         match self {
             Statement::Let(stmt) => write!(f, "{stmt}"),
             Statement::Return(stmt) => write!(f, "{stmt}"),
-            Statement::Expression(stmt) => write!(f, "{stmt}"),
+            Statement::Expression(stmt) if stmt.is_block_like() => write!(f, "{stmt}"),
+            Statement::Expression(stmt) => write!(f, "{stmt};"),
+            Statement::While(stmt) => write!(f, "{stmt}"),
+            Statement::For(stmt) => write!(f, "{stmt}"),
+            Statement::Break(stmt) => write!(f, "{stmt}"),
+            Statement::Continue(stmt) => write!(f, "{stmt}"),
+            Statement::Assign(stmt) => write!(f, "{stmt}"),
         }
     }
 }
 
 #[derive(Debug)]
 pub struct LetStatement {
-    pub modifier: Token,
+    /// The `///` doc comments preceding the declaration.
+    pub docs: Vec<String>,
+    pub modifier: Token<'static>,
     pub name: Identifier,
+    pub type_annotation: Option<TypeExpr>,
     pub value: Expression,
+    pub span: Span,
 }
 
 impl Display for LetStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} = {};", self.modifier, self.name, self.value)
+        write!(f, "{} {}", self.modifier, self.name)?;
+        if let Some(type_annotation) = &self.type_annotation {
+            write!(f, ": {type_annotation}")?;
+        }
+        write!(f, " = {};", self.value)
     }
 }
 
 impl PartialEq for LetStatement {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name && self.value == other.value
+        self.modifier == other.modifier && self.name == other.name && self.type_annotation == other.type_annotation && self.value == other.value
     }
 }
 
 impl LetStatement {
-    pub fn new(modifier: Token, name: Identifier) -> LetStatement {
+    pub fn new(modifier: Token<'static>, name: Identifier, type_annotation: Option<TypeExpr>, value: Expression, span: Span) -> LetStatement {
         LetStatement {
+            docs: Vec::new(),
             modifier,
             name,
-            value: Expression::Blank,
+            type_annotation,
+            value,
+            span,
         }
     }
 }
@@ -74,6 +99,7 @@ impl LetStatement {
 #[derive(Debug)]
 pub struct ReturnStatement {
     pub return_value: Expression,
+    pub span: Span,
 }
 
 impl Display for ReturnStatement {
@@ -89,36 +115,253 @@ impl PartialEq for ReturnStatement {
 }
 
 impl ReturnStatement {
-    pub fn new() -> ReturnStatement {
+    pub fn new(return_value: Expression, span: Span) -> ReturnStatement {
         ReturnStatement {
-            return_value: Expression::Blank,
+            return_value,
+            span,
         }
     }
 }
 
-//  FIXME: add values
-#[derive(Debug, PartialEq)]
-pub enum Expression {
-    
+/// `target = value;`, or a compound assignment such as `target += value;`
+/// when `operator` is set.
+///
+/// The parser only accepts identifiers, index and field expressions as the
+/// target.
+#[derive(Debug)]
+pub struct AssignStatement {
+    pub target: Expression,
+    pub operator: Option<InfixOperator>,
+    pub value: Expression,
+    pub span: Span,
+}
+
+impl Display for AssignStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operator {
+            Some(operator) => write!(f, "{} {}= {};", self.target, operator, self.value),
+            None => write!(f, "{} = {};", self.target, self.value),
+        }
+    }
+}
+
+impl PartialEq for AssignStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.target == other.target && self.operator == other.operator && self.value == other.value
+    }
+}
+
+impl AssignStatement {
+    pub fn new(target: Expression, operator: Option<InfixOperator>, value: Expression, span: Span) -> AssignStatement {
+        AssignStatement {
+            target,
+            operator,
+            value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WhileStatement {
+    pub condition: Expression,
+    pub body: BlockStatement,
+    pub span: Span,
+}
+
+impl Display for WhileStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "while {} {}", self.condition, self.body)
+    }
+}
+
+impl PartialEq for WhileStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition == other.condition && self.body == other.body
+    }
+}
 
+impl WhileStatement {
+    pub fn new(condition: Expression, body: BlockStatement, span: Span) -> WhileStatement {
+        WhileStatement {
+            condition,
+            body,
+            span,
+        }
+    }
+}
 
+/// `for variable in iterable { ... }`, where `variable` is a new binding
+/// scoped to the body.
+#[derive(Debug)]
+pub struct ForStatement {
+    pub variable: Identifier,
+    pub iterable: Expression,
+    pub body: BlockStatement,
+    pub span: Span,
+}
 
+impl Display for ForStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "for {} in {} {}", self.variable, self.iterable, self.body)
+    }
+}
 
-    Blank,
+impl PartialEq for ForStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.variable == other.variable && self.iterable == other.iterable && self.body == other.body
+    }
+}
+
+impl ForStatement {
+    pub fn new(variable: Identifier, iterable: Expression, body: BlockStatement, span: Span) -> ForStatement {
+        ForStatement {
+            variable,
+            iterable,
+            body,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BreakStatement {
+    pub span: Span,
+}
+
+impl Display for BreakStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "break;")
+    }
+}
+
+impl PartialEq for BreakStatement {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl BreakStatement {
+    pub fn new(span: Span) -> BreakStatement {
+        BreakStatement {
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ContinueStatement {
+    pub span: Span,
+}
+
+impl Display for ContinueStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "continue;")
+    }
+}
+
+impl PartialEq for ContinueStatement {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl ContinueStatement {
+    pub fn new(span: Span) -> ContinueStatement {
+        ContinueStatement {
+            span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Expression {
+    Identifier(Identifier),
+    Integer(IntegerLiteral),
+    Float(FloatLiteral),
+    Boolean(BooleanLiteral),
+    Str(StringLiteral),
+    Null(NullLiteral),
+    Prefix(PrefixExpression),
+    Infix(InfixExpression),
+    Logical(LogicalExpression),
+    Range(RangeExpression),
+    Function(FunctionLiteral),
+    Call(CallExpression),
+    Index(IndexExpression),
+    Field(FieldExpression),
+    If(IfExpression),
+    Block(BlockStatement),
 }
 
 impl Display for Expression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            _ => write!(f, "Expression")
+            Expression::Identifier(expr) => write!(f, "{expr}"),
+            Expression::Integer(expr) => write!(f, "{expr}"),
+            Expression::Float(expr) => write!(f, "{expr}"),
+            Expression::Boolean(expr) => write!(f, "{expr}"),
+            Expression::Str(expr) => write!(f, "{expr}"),
+            Expression::Null(expr) => write!(f, "{expr}"),
+            Expression::Prefix(expr) => write!(f, "{expr}"),
+            Expression::Infix(expr) => write!(f, "{expr}"),
+            Expression::Logical(expr) => write!(f, "{expr}"),
+            Expression::Range(expr) => write!(f, "{expr}"),
+            Expression::Function(expr) => write!(f, "{expr}"),
+            Expression::Call(expr) => write!(f, "{expr}"),
+            Expression::Index(expr) => write!(f, "{expr}"),
+            Expression::Field(expr) => write!(f, "{expr}"),
+            Expression::If(expr) => write!(f, "{expr}"),
+            Expression::Block(expr) => write!(f, "{expr}"),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl Expression {
+    pub fn span(&self) -> Span {
+        return match self {
+            Expression::Identifier(expr) => expr.span,
+            Expression::Integer(expr) => expr.span,
+            Expression::Float(expr) => expr.span,
+            Expression::Boolean(expr) => expr.span,
+            Expression::Str(expr) => expr.span,
+            Expression::Null(expr) => expr.span,
+            Expression::Prefix(expr) => expr.span,
+            Expression::Infix(expr) => expr.span,
+            Expression::Logical(expr) => expr.span,
+            Expression::Range(expr) => expr.span,
+            Expression::Function(expr) => expr.span,
+            Expression::Call(expr) => expr.span,
+            Expression::Index(expr) => expr.span,
+            Expression::Field(expr) => expr.span,
+            Expression::If(expr) => expr.span,
+            Expression::Block(expr) => expr.span,
+        };
+    }
+
+    /// Whether the expression ends with a block, and so doesn't need a
+    /// trailing `;` when used as the value of a statement.
+    pub fn is_block_like(&self) -> bool {
+        matches!(self, Expression::Function(_) | Expression::If(_) | Expression::Block(_))
+    }
+
+    /// Whether the expression names a place that can be assigned to.
+    pub fn is_place(&self) -> bool {
+        matches!(self, Expression::Identifier(_) | Expression::Index(_) | Expression::Field(_))
+    }
+}
+
+#[derive(Debug)]
 pub struct Identifier {
-    pub token: Token,
-    pub value: String,
+    pub token: Token<'static>,
+    pub value: Symbol,
+    pub span: Span,
+}
+
+impl PartialEq for Identifier {
+    fn eq(&self, other: &Self) -> bool {
+        self.token == other.token && self.value == other.value
+    }
 }
 
 impl Display for Identifier {
@@ -128,28 +371,829 @@ impl Display for Identifier {
 }
 
 impl Identifier {
-    pub fn new(token: Token, value: String) -> Identifier {
+    pub fn new(token: Token<'static>, value: Symbol, span: Span) -> Identifier {
         Identifier {
             token,
             value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IntegerLiteral {
+    pub value: u64,
+    pub suffix: Option<NumSuffix>,
+    pub span: Span,
+}
+
+impl PartialEq for IntegerLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.suffix == other.suffix
+    }
+}
+
+impl Display for IntegerLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.value, NumSuffix::display(&self.suffix))
+    }
+}
+
+impl IntegerLiteral {
+    pub fn new(value: u64, suffix: Option<NumSuffix>, span: Span) -> IntegerLiteral {
+        IntegerLiteral {
+            value,
+            suffix,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatLiteral {
+    pub value: f64,
+    pub suffix: Option<NumSuffix>,
+    pub span: Span,
+}
+
+impl PartialEq for FloatLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.suffix == other.suffix
+    }
+}
+
+impl Display for FloatLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}{}", self.value, NumSuffix::display(&self.suffix))
+    }
+}
+
+impl FloatLiteral {
+    pub fn new(value: f64, suffix: Option<NumSuffix>, span: Span) -> FloatLiteral {
+        FloatLiteral {
+            value,
+            suffix,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BooleanLiteral {
+    pub value: bool,
+    pub span: Span,
+}
+
+impl PartialEq for BooleanLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Display for BooleanLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl BooleanLiteral {
+    pub fn new(value: bool, span: Span) -> BooleanLiteral {
+        BooleanLiteral {
+            value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StringLiteral {
+    pub value: String,
+    pub span: Span,
+}
+
+impl PartialEq for StringLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Display for StringLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"", self.value.escape_default())
+    }
+}
+
+impl StringLiteral {
+    pub fn new(value: String, span: Span) -> StringLiteral {
+        StringLiteral {
+            value,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NullLiteral {
+    pub span: Span,
+}
+
+impl Display for NullLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "null")
+    }
+}
+
+impl PartialEq for NullLiteral {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl NullLiteral {
+    pub fn new(span: Span) -> NullLiteral {
+        NullLiteral {
+            span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PrefixOperator {
+    Not,
+    Negate,
+}
+
+impl Display for PrefixOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrefixOperator::Not => write!(f, "!"),
+            PrefixOperator::Negate => write!(f, "-"),
+        }
+    }
+}
+
+impl PrefixOperator {
+    pub fn from_token(token: &Token) -> Option<PrefixOperator> {
+        return match token {
+            Token::Bang => Some(PrefixOperator::Not),
+            Token::Dash => Some(PrefixOperator::Negate),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Debug)]
+pub struct PrefixExpression {
+    pub operator: PrefixOperator,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+impl PartialEq for PrefixExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.operator == other.operator && self.right == other.right
+    }
+}
+
+impl Display for PrefixExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}{})", self.operator, self.right)
+    }
+}
+
+impl PrefixExpression {
+    pub fn new(operator: PrefixOperator, right: Expression, span: Span) -> PrefixExpression {
+        PrefixExpression {
+            operator,
+            right: Box::new(right),
+            span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InfixOperator {
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
+    Eq,
+    NotEq,
+    Lt,
+    Gt,
+    LtEq,
+    GtEq,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+impl Display for InfixOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InfixOperator::Plus => write!(f, "+"),
+            InfixOperator::Minus => write!(f, "-"),
+            InfixOperator::Multiply => write!(f, "*"),
+            InfixOperator::Divide => write!(f, "/"),
+            InfixOperator::Modulo => write!(f, "%"),
+            InfixOperator::Eq => write!(f, "=="),
+            InfixOperator::NotEq => write!(f, "!="),
+            InfixOperator::Lt => write!(f, "<"),
+            InfixOperator::Gt => write!(f, ">"),
+            InfixOperator::LtEq => write!(f, "<="),
+            InfixOperator::GtEq => write!(f, ">="),
+            InfixOperator::BitAnd => write!(f, "&"),
+            InfixOperator::BitOr => write!(f, "|"),
+            InfixOperator::BitXor => write!(f, "^"),
+            InfixOperator::Shl => write!(f, "<<"),
+            InfixOperator::Shr => write!(f, ">>"),
+        }
+    }
+}
+
+impl InfixOperator {
+    pub fn from_token(token: &Token) -> Option<InfixOperator> {
+        return match token {
+            Token::Plus => Some(InfixOperator::Plus),
+            Token::Dash => Some(InfixOperator::Minus),
+            Token::Asterisk => Some(InfixOperator::Multiply),
+            Token::Slash => Some(InfixOperator::Divide),
+            Token::Percent => Some(InfixOperator::Modulo),
+            Token::Eq => Some(InfixOperator::Eq),
+            Token::NotEq => Some(InfixOperator::NotEq),
+            Token::Lt => Some(InfixOperator::Lt),
+            Token::Gt => Some(InfixOperator::Gt),
+            Token::LtEq => Some(InfixOperator::LtEq),
+            Token::GtEq => Some(InfixOperator::GtEq),
+            Token::Ampersand => Some(InfixOperator::BitAnd),
+            Token::Pipe => Some(InfixOperator::BitOr),
+            Token::Caret => Some(InfixOperator::BitXor),
+            Token::Shl => Some(InfixOperator::Shl),
+            Token::Shr => Some(InfixOperator::Shr),
+            _ => None,
+        };
+    }
+
+    /// Returns the operator applied by a compound assignment such as `+=`.
+    pub fn from_compound_assign(token: &Token) -> Option<InfixOperator> {
+        return match token {
+            Token::PlusAssign => Some(InfixOperator::Plus),
+            Token::DashAssign => Some(InfixOperator::Minus),
+            Token::AsteriskAssign => Some(InfixOperator::Multiply),
+            Token::SlashAssign => Some(InfixOperator::Divide),
+            Token::PercentAssign => Some(InfixOperator::Modulo),
+            Token::AmpersandAssign => Some(InfixOperator::BitAnd),
+            Token::PipeAssign => Some(InfixOperator::BitOr),
+            Token::CaretAssign => Some(InfixOperator::BitXor),
+            Token::ShlAssign => Some(InfixOperator::Shl),
+            Token::ShrAssign => Some(InfixOperator::Shr),
+            _ => None,
+        };
+    }
+}
+
+#[derive(Debug)]
+pub struct InfixExpression {
+    pub left: Box<Expression>,
+    pub operator: InfixOperator,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+impl PartialEq for InfixExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left && self.operator == other.operator && self.right == other.right
+    }
+}
+
+impl Display for InfixExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({} {} {})", self.left, self.operator, self.right)
+    }
+}
+
+impl InfixExpression {
+    pub fn new(left: Expression, operator: InfixOperator, right: Expression, span: Span) -> InfixExpression {
+        InfixExpression {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+            span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LogicalOperator {
+    And,
+    Or,
+}
+
+impl Display for LogicalOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogicalOperator::And => write!(f, "&&"),
+            LogicalOperator::Or => write!(f, "||"),
+        }
+    }
+}
+
+impl LogicalOperator {
+    pub fn from_token(token: &Token) -> Option<LogicalOperator> {
+        return match token {
+            Token::And => Some(LogicalOperator::And),
+            Token::Or => Some(LogicalOperator::Or),
+            _ => None,
+        };
+    }
+}
+
+/// `&&` and `||`, kept apart from `InfixExpression` as they short-circuit:
+/// `right` is only evaluated when `left` doesn't already decide the result.
+#[derive(Debug)]
+pub struct LogicalExpression {
+    pub left: Box<Expression>,
+    pub operator: LogicalOperator,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+impl Display for LogicalExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({} {} {})", self.left, self.operator, self.right)
+    }
+}
+
+impl PartialEq for LogicalExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.left == other.left && self.operator == other.operator && self.right == other.right
+    }
+}
+
+impl LogicalExpression {
+    pub fn new(left: Expression, operator: LogicalOperator, right: Expression, span: Span) -> LogicalExpression {
+        LogicalExpression {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+            span,
+        }
+    }
+}
+
+/// `start..end`, or `start..=end` when `inclusive`.
+#[derive(Debug)]
+pub struct RangeExpression {
+    pub start: Box<Expression>,
+    pub end: Box<Expression>,
+    pub inclusive: bool,
+    pub span: Span,
+}
+
+impl Display for RangeExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "({}{}{})", self.start, operator, self.end)
+    }
+}
+
+impl PartialEq for RangeExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.start == other.start && self.end == other.end && self.inclusive == other.inclusive
+    }
+}
+
+impl RangeExpression {
+    pub fn new(start: Expression, end: Expression, inclusive: bool, span: Span) -> RangeExpression {
+        RangeExpression {
+            start: Box::new(start),
+            end: Box::new(end),
+            inclusive,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockStatement {
+    pub statements: Vec<Statement>,
+    /// The final expression without a `;`, which is the value of the block.
+    pub tail: Option<Box<Expression>>,
+    pub span: Span,
+}
+
+impl Display for BlockStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for statement in &self.statements {
+            write!(f, " {statement}")?;
+        }
+        if let Some(tail) = &self.tail {
+            write!(f, " {tail}")?;
+        }
+        write!(f, " }}")
+    }
+}
+
+impl PartialEq for BlockStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements && self.tail == other.tail
+    }
+}
+
+impl BlockStatement {
+    pub fn new(statements: Vec<Statement>, tail: Option<Expression>, span: Span) -> BlockStatement {
+        BlockStatement {
+            statements,
+            tail: tail.map(Box::new),
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IfExpression {
+    pub condition: Box<Expression>,
+    pub consequence: BlockStatement,
+    /// Either another `IfExpression` for `else if`, or a `Block` for `else`.
+    pub alternative: Option<Box<Expression>>,
+    pub span: Span,
+}
+
+impl Display for IfExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "if {} {}", self.condition, self.consequence)?;
+        if let Some(alternative) = &self.alternative {
+            write!(f, " else {alternative}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for IfExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition == other.condition && self.consequence == other.consequence && self.alternative == other.alternative
+    }
+}
+
+impl IfExpression {
+    pub fn new(condition: Expression, consequence: BlockStatement, alternative: Option<Expression>, span: Span) -> IfExpression {
+        IfExpression {
+            condition: Box::new(condition),
+            consequence,
+            alternative: alternative.map(Box::new),
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Parameter {
+    pub name: Identifier,
+    pub param_type: TypeExpr,
+    pub span: Span,
+}
+
+impl Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.param_type)
+    }
+}
+
+impl PartialEq for Parameter {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.param_type == other.param_type
+    }
+}
+
+impl Parameter {
+    pub fn new(name: Identifier, param_type: TypeExpr, span: Span) -> Parameter {
+        Parameter {
+            name,
+            param_type,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FunctionLiteral {
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<TypeExpr>,
+    pub body: BlockStatement,
+    pub span: Span,
+}
+
+impl Display for FunctionLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parameters: Vec<String> = self.parameters.iter().map(|param| param.to_string()).collect();
+        write!(f, "fn({})", parameters.join(", "))?;
+        if let Some(return_type) = &self.return_type {
+            write!(f, " -> {return_type}")?;
+        }
+        write!(f, " {}", self.body)
+    }
+}
+
+impl PartialEq for FunctionLiteral {
+    fn eq(&self, other: &Self) -> bool {
+        self.parameters == other.parameters && self.return_type == other.return_type && self.body == other.body
+    }
+}
+
+impl FunctionLiteral {
+    pub fn new(parameters: Vec<Parameter>, return_type: Option<TypeExpr>, body: BlockStatement, span: Span) -> FunctionLiteral {
+        FunctionLiteral {
+            parameters,
+            return_type,
+            body,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CallExpression {
+    pub function: Box<Expression>,
+    pub arguments: Vec<Expression>,
+    pub span: Span,
+}
+
+impl Display for CallExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let arguments: Vec<String> = self.arguments.iter().map(|arg| arg.to_string()).collect();
+        write!(f, "{}({})", self.function, arguments.join(", "))
+    }
+}
+
+impl PartialEq for CallExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.function == other.function && self.arguments == other.arguments
+    }
+}
+
+impl CallExpression {
+    pub fn new(function: Expression, arguments: Vec<Expression>, span: Span) -> CallExpression {
+        CallExpression {
+            function: Box::new(function),
+            arguments,
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexExpression {
+    pub object: Box<Expression>,
+    pub index: Box<Expression>,
+    pub span: Span,
+}
+
+impl Display for IndexExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]", self.object, self.index)
+    }
+}
+
+impl PartialEq for IndexExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.object == other.object && self.index == other.index
+    }
+}
+
+impl IndexExpression {
+    pub fn new(object: Expression, index: Expression, span: Span) -> IndexExpression {
+        IndexExpression {
+            object: Box::new(object),
+            index: Box::new(index),
+            span,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FieldExpression {
+    pub object: Box<Expression>,
+    pub field: Identifier,
+    pub span: Span,
+}
+
+impl Display for FieldExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.object, self.field)
+    }
+}
+
+impl PartialEq for FieldExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.object == other.object && self.field == other.field
+    }
+}
+
+impl FieldExpression {
+    pub fn new(object: Expression, field: Identifier, span: Span) -> FieldExpression {
+        FieldExpression {
+            object: Box::new(object),
+            field,
+            span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TypeExpr {
+    Primitive(PrimitiveType),
+    Optional(OptionalType),
+    Array(ArrayType),
+    Function(FunctionType),
+}
+
+impl Display for TypeExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeExpr::Primitive(ty) => write!(f, "{ty}"),
+            TypeExpr::Optional(ty) => write!(f, "{ty}"),
+            TypeExpr::Array(ty) => write!(f, "{ty}"),
+            TypeExpr::Function(ty) => write!(f, "{ty}"),
+        }
+    }
+}
+
+impl TypeExpr {
+    pub fn span(&self) -> Span {
+        return match self {
+            TypeExpr::Primitive(ty) => ty.span,
+            TypeExpr::Optional(ty) => ty.span,
+            TypeExpr::Array(ty) => ty.span,
+            TypeExpr::Function(ty) => ty.span,
+        };
+    }
+}
+
+/// One of the built in types, `i32`, `bool`, `String` etc.
+#[derive(Debug)]
+pub struct PrimitiveType {
+    pub token: Token<'static>,
+    pub span: Span,
+}
+
+impl Display for PrimitiveType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token)
+    }
+}
+
+impl PartialEq for PrimitiveType {
+    fn eq(&self, other: &Self) -> bool {
+        self.token == other.token
+    }
+}
+
+impl PrimitiveType {
+    pub fn new(token: Token<'static>, span: Span) -> PrimitiveType {
+        PrimitiveType {
+            token,
+            span,
+        }
+    }
+}
+
+/// `?T`, either a `T` or `null`.
+#[derive(Debug)]
+pub struct OptionalType {
+    pub inner: Box<TypeExpr>,
+    pub span: Span,
+}
+
+impl Display for OptionalType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "?{}", self.inner)
+    }
+}
+
+impl PartialEq for OptionalType {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl OptionalType {
+    pub fn new(inner: TypeExpr, span: Span) -> OptionalType {
+        OptionalType {
+            inner: Box::new(inner),
+            span,
+        }
+    }
+}
+
+/// `[T]` or, with a fixed length, `[N]T`.
+#[derive(Debug)]
+pub struct ArrayType {
+    pub element: Box<TypeExpr>,
+    pub length: Option<u64>,
+    pub span: Span,
+}
+
+impl Display for ArrayType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self.length {
+            Some(length) => write!(f, "[{length}]{}", self.element),
+            None => write!(f, "[{}]", self.element),
+        };
+    }
+}
+
+impl PartialEq for ArrayType {
+    fn eq(&self, other: &Self) -> bool {
+        self.element == other.element && self.length == other.length
+    }
+}
+
+impl ArrayType {
+    pub fn new(element: TypeExpr, length: Option<u64>, span: Span) -> ArrayType {
+        ArrayType {
+            element: Box::new(element),
+            length,
+            span,
+        }
+    }
+}
+
+/// `fn(T, U) -> R`
+#[derive(Debug)]
+pub struct FunctionType {
+    pub parameters: Vec<TypeExpr>,
+    pub return_type: Option<Box<TypeExpr>>,
+    pub span: Span,
+}
+
+impl Display for FunctionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parameters: Vec<String> = self.parameters.iter().map(|param| param.to_string()).collect();
+        write!(f, "fn({})", parameters.join(", "))?;
+        if let Some(return_type) = &self.return_type {
+            write!(f, " -> {return_type}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for FunctionType {
+    fn eq(&self, other: &Self) -> bool {
+        self.parameters == other.parameters && self.return_type == other.return_type
+    }
+}
+
+impl FunctionType {
+    pub fn new(parameters: Vec<TypeExpr>, return_type: Option<TypeExpr>, span: Span) -> FunctionType {
+        FunctionType {
+            parameters,
+            return_type: return_type.map(Box::new),
+            span,
         }
     }
 }
 
 #[test]
 fn test_string_conversion() {
+    let span = Span::default();
     let program: Program = Program::new(
         vec![
             Statement::Let(LetStatement::new(
                 Token::Const,
-                Identifier::new(Token::Ident(String::from("x")), String::from("x")),
+                Identifier::new(Token::Ident("x".into()), Symbol::intern("x"), span),
+                Some(TypeExpr::Optional(OptionalType::new(TypeExpr::Primitive(PrimitiveType::new(Token::I32, span)), span))),
+                Expression::Integer(IntegerLiteral::new(5, None, span)),
+                span,
             )),
             Statement::Let(LetStatement::new(
                 Token::Var,
-                Identifier::new(Token::Ident(String::from("y")), String::from("y")),
+                Identifier::new(Token::Ident("y".into()), Symbol::intern("y"), span),
+                None,
+                Expression::Infix(InfixExpression::new(
+                    Expression::Identifier(Identifier::new(Token::Ident("x".into()), Symbol::intern("x"), span)),
+                    InfixOperator::Plus,
+                    Expression::Prefix(PrefixExpression::new(PrefixOperator::Negate, Expression::Float(FloatLiteral::new(4.2, None, span)), span)),
+                    span,
+                )),
+                span,
             )),
         ],
+        span,
     );
 
-    assert_eq!(program.to_string(), "const x = Expression;\nvar y = Expression;\n");
+    assert_eq!(program.to_string(), "const x: ?i32 = 5;\nvar y = (x + (-4.2));\n");
+
+    let let_x = |modifier| LetStatement::new(
+        modifier,
+        Identifier::new(Token::Ident("x".into()), Symbol::intern("x"), span),
+        None,
+        Expression::Integer(IntegerLiteral::new(5, None, span)),
+        span,
+    );
+    assert_eq!(let_x(Token::Const), let_x(Token::Const));
+    assert_ne!(let_x(Token::Const), let_x(Token::Var));
 }
@@ -1,21 +1,68 @@
-use crate::lexer::Lexer;
+use crate::lexer::{ Lexer, LexError };
 use crate::lexer::Token;
+use crate::source::Span;
 use crate::parser::{ ast, parser_errors::{ ParserErrors, ParserError } };
 
-pub struct Parser {
-    lexer: Lexer,
-    curr_tkn: Token,
-    peek_tkn: Token,
-    errors: ParserErrors
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+enum Precedence {
+    Lowest,
+    Range,
+    Or,
+    And,
+    Equals,
+    LessGreater,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
+    Sum,
+    Product,
+    Prefix,
+    Call,
 }
 
-impl Parser {
-    pub fn new(lexer: Lexer) -> Parser {
-        let mut parser: Parser = Parser {
+impl Precedence {
+    fn of(token: &Token) -> Precedence {
+        return match token {
+            Token::DotDot | Token::DotDotEq => Precedence::Range,
+            Token::Or => Precedence::Or,
+            Token::And => Precedence::And,
+            Token::Eq | Token::NotEq => Precedence::Equals,
+            Token::Lt | Token::Gt | Token::LtEq | Token::GtEq => Precedence::LessGreater,
+            Token::Pipe => Precedence::BitOr,
+            Token::Caret => Precedence::BitXor,
+            Token::Ampersand => Precedence::BitAnd,
+            Token::Shl | Token::Shr => Precedence::Shift,
+            Token::Plus | Token::Dash => Precedence::Sum,
+            Token::Asterisk | Token::Slash | Token::Percent => Precedence::Product,
+            Token::LParen | Token::LBracket | Token::Dot => Precedence::Call,
+            _ => Precedence::Lowest,
+        };
+    }
+}
+
+pub struct Parser<'a> {
+    lexer: Lexer<'a>,
+    curr_tkn: Token<'a>,
+    peek_tkn: Token<'a>,
+    curr_span: Span,
+    peek_span: Span,
+    errors: ParserErrors,
+    /// How many loops enclose the current position within the innermost
+    /// function, for checking `break` and `continue`.
+    loop_depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(lexer: Lexer<'a>) -> Parser<'a> {
+        let mut parser: Parser<'a> = Parser {
             lexer,
-            curr_tkn: Token::Illegal(0),
-            peek_tkn: Token::Illegal(0),
+            curr_tkn: Token::Eof,
+            peek_tkn: Token::Eof,
+            curr_span: Span::default(),
+            peek_span: Span::default(),
             errors: ParserErrors::default(),
+            loop_depth: 0,
         };
 
         parser.next();
@@ -26,72 +73,702 @@ impl Parser {
 
     pub fn next(&mut self) {
         self.curr_tkn = self.peek_tkn.clone();
-        self.peek_tkn = self.lexer.next();
+        self.curr_span = self.peek_span;
+
+        let next = self.lexer.next_token();
+        self.peek_tkn = next.token;
+        self.peek_span = next.span;
+    }
+
+    pub fn errors(&self) -> &ParserErrors {
+        &self.errors
+    }
+
+    pub fn lex_errors(&self) -> &[LexError] {
+        self.lexer.errors()
     }
 
     pub fn parse_program(&mut self) -> ast::Program {
-        let mut program: ast::Program = ast::Program::new(Vec::new());
+        let start = self.curr_span;
+        let mut program: ast::Program = ast::Program::new(Vec::new(), start);
 
         while self.curr_tkn != Token::Eof {
-            if let Some(stmt) = self.parse_statement() {
-                program.statements.push(stmt);
+            if self.curr_tok_is(&Token::RSquirly) {
+                self.errors.push_err(ParserError::Unexpected(Token::RSquirly), self.curr_span);
+                self.next();
+                continue;
+            }
+
+            match self.parse_statement() {
+                Some(stmt) => {
+                    program.statements.push(stmt);
+                    self.next();
+                },
+                None => self.synchronize(),
             }
-            self.next();
         }
 
+        program.span = Span::new(start.file, 0, self.curr_span.end, 1, 1);
         program
     }
 
     fn parse_statement(&mut self) -> Option<ast::Statement> {
         return match self.curr_tkn {
-            Token::Var | Token::Const => self.parse_let_stmt(self.curr_tkn.clone()).map(ast::Statement::Let),
+            Token::Var | Token::Const => self.parse_let_stmt(self.curr_tkn.clone().into_owned()).map(ast::Statement::Let),
             Token::Return => self.parse_return_stmt().map(ast::Statement::Return),
-            _ => None,
+            Token::DocComment(_) => self.parse_documented_stmt(),
+            Token::While => self.parse_while_stmt().map(ast::Statement::While),
+            Token::For => self.parse_for_stmt().map(ast::Statement::For),
+            Token::Break | Token::Continue => self.parse_loop_control_stmt(),
+            _ => self.parse_expression_stmt(),
+        };
+    }
+
+    /// Collects consecutive doc comments and attaches them to the
+    /// declaration that follows.
+    fn parse_documented_stmt(&mut self) -> Option<ast::Statement> {
+        let start = self.curr_span;
+        let mut docs = Vec::new();
+        while let Token::DocComment(text) = self.curr_tkn.clone() {
+            docs.push(text.into_owned());
+            self.next();
+        }
+
+        if let Token::Var | Token::Const = self.curr_tkn {
+            let mut stmt = self.parse_let_stmt(self.curr_tkn.clone().into_owned())?;
+            stmt.docs = docs;
+            return Some(ast::Statement::Let(stmt));
+        }
+
+        self.errors.push_err(ParserError::DanglingDocComment, start);
+        return match self.curr_tkn {
+            Token::RSquirly | Token::Eof => None,
+            _ => self.parse_statement(),
         };
     }
 
-    fn parse_let_stmt(&mut self, modifier: Token) -> Option<ast::LetStatement> {
-        if !self.expect_peek(&Token::Ident(String::new())) {
-            self.errors.push_err(ParserError::IdentifierExpected);
+    fn parse_let_stmt(&mut self, modifier: Token<'static>) -> Option<ast::LetStatement> {
+        let start = self.curr_span;
+
+        if !self.expect_peek(&Token::Ident("".into())) {
+            self.errors.push_err(ParserError::IdentifierExpected, self.peek_span);
             return None;
         }
 
         let name = match self.curr_tkn.clone() {
-            Token::Ident(value) => ast::Identifier::new(self.curr_tkn.clone(), value),
+            Token::Ident(value) => ast::Identifier::new(self.curr_tkn.clone().into_owned(), value, self.curr_span),
             _ => unreachable!(),
         };
 
+        let mut type_annotation = None;
+        if self.peek_tok_is(&Token::Colon) {
+            self.next();
+            self.next();
+            type_annotation = Some(self.parse_type()?);
+        }
+
         if !self.expect_peek(&Token::Assign) {
-            self.errors.push_err(ParserError::PeekError(Token::Assign, self.peek_tkn.clone()));
+            self.errors.push_err(ParserError::PeekError(Token::Assign, self.peek_tkn.clone().into_owned()), self.peek_span);
             return None;
         }
 
-        //  TODO: Do expressions, currently skipping until semicolon is encountered
-        //  TODO: types
-        while !self.curr_tok_is(&Token::Semicolon) {
-            self.next();
+        self.next();
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        if value.is_block_like() && !self.peek_tok_is(&Token::Semicolon) {
+            return Some(ast::LetStatement::new(modifier, name, type_annotation, value, start.to(self.curr_span)));
         }
 
-        Some(ast::LetStatement::new(modifier, name))
+        if !self.expect_peek(&Token::Semicolon) {
+            self.errors.push_err(ParserError::PeekError(Token::Semicolon, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        Some(ast::LetStatement::new(modifier, name, type_annotation, value, start.to(self.curr_span)))
     }
 
     fn parse_return_stmt(&mut self) -> Option<ast::ReturnStatement> {
-        let stmt = ast::ReturnStatement::new();
+        let start = self.curr_span;
+        self.next();
+        let return_value = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(&Token::Semicolon) {
+            self.errors.push_err(ParserError::PeekError(Token::Semicolon, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        Some(ast::ReturnStatement::new(return_value, start.to(self.curr_span)))
+    }
+
+    fn parse_while_stmt(&mut self) -> Option<ast::WhileStatement> {
+        let start = self.curr_span;
+        self.next();
+        let condition = self.parse_expression(Precedence::Lowest)?;
+
+        let body = self.parse_loop_body()?;
+
+        Some(ast::WhileStatement::new(condition, body, start.to(self.curr_span)))
+    }
+
+    fn parse_for_stmt(&mut self) -> Option<ast::ForStatement> {
+        let start = self.curr_span;
+
+        if !self.expect_peek(&Token::Ident("".into())) {
+            self.errors.push_err(ParserError::IdentifierExpected, self.peek_span);
+            return None;
+        }
+
+        let variable = match self.curr_tkn.clone() {
+            Token::Ident(value) => ast::Identifier::new(self.curr_tkn.clone().into_owned(), value, self.curr_span),
+            _ => unreachable!(),
+        };
+
+        if !self.expect_peek(&Token::In) {
+            self.errors.push_err(ParserError::PeekError(Token::In, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        self.next();
+        let iterable = self.parse_expression(Precedence::Lowest)?;
+
+        let body = self.parse_loop_body()?;
+
+        Some(ast::ForStatement::new(variable, iterable, body, start.to(self.curr_span)))
+    }
+
+    /// Parses the `{ ... }` of a loop, inside which `break` and `continue`
+    /// are allowed.
+    fn parse_loop_body(&mut self) -> Option<ast::BlockStatement> {
+        if !self.expect_peek(&Token::LSquirly) {
+            self.errors.push_err(ParserError::PeekError(Token::LSquirly, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        self.loop_depth += 1;
+        let body = self.parse_block();
+        self.loop_depth -= 1;
+
+        body
+    }
+
+    /// Parses `break;` or `continue;`, reporting them when outside a loop.
+    fn parse_loop_control_stmt(&mut self) -> Option<ast::Statement> {
+        let keyword = self.curr_tkn.clone().into_owned();
+        let start = self.curr_span;
+
+        if self.loop_depth == 0 {
+            self.errors.push_err(ParserError::OutsideLoop(keyword.clone()), start);
+        }
+
+        if !self.expect_peek(&Token::Semicolon) {
+            self.errors.push_err(ParserError::PeekError(Token::Semicolon, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        let span = start.to(self.curr_span);
+        return match keyword {
+            Token::Break => Some(ast::Statement::Break(ast::BreakStatement::new(span))),
+            _ => Some(ast::Statement::Continue(ast::ContinueStatement::new(span))),
+        };
+    }
+
+    fn parse_expression(&mut self, precedence: Precedence) -> Option<ast::Expression> {
+        let mut left = self.parse_prefix()?;
+
+        while !self.peek_tok_is(&Token::Semicolon) && precedence < Precedence::of(&self.peek_tkn) {
+            //  a block-like value may end its `let` without a `;`, so a `}` that
+            //  ends the line ends the expression, and `-x` or `(x)` on the
+            //  next line starts a new statement
+            if left.is_block_like() && self.peek_span.line != self.curr_span.line {
+                break;
+            }
+
+            if self.peek_tok_is(&Token::LParen) {
+                self.next();
+                left = self.parse_call(left).map(ast::Expression::Call)?;
+                continue;
+            }
+
+            if self.peek_tok_is(&Token::LBracket) {
+                self.next();
+                left = self.parse_index(left).map(ast::Expression::Index)?;
+                continue;
+            }
+
+            if self.peek_tok_is(&Token::Dot) {
+                self.next();
+                left = self.parse_field(left).map(ast::Expression::Field)?;
+                continue;
+            }
+
+            if let Some(operator) = ast::LogicalOperator::from_token(&self.peek_tkn) {
+                self.next();
+                left = self.parse_logical(left, operator)?;
+                continue;
+            }
+
+            if self.peek_tok_is(&Token::DotDot) || self.peek_tok_is(&Token::DotDotEq) {
+                self.next();
+                left = self.parse_range(left)?;
+                continue;
+            }
+
+            let operator = match ast::InfixOperator::from_token(&self.peek_tkn) {
+                Some(operator) => operator,
+                None => return Some(left),
+            };
+            self.next();
+            left = self.parse_infix(left, operator)?;
+        }
+
+        Some(left)
+    }
+
+    fn parse_prefix(&mut self) -> Option<ast::Expression> {
+        let span = self.curr_span;
+        return match self.curr_tkn.clone() {
+            Token::Ident(value) => Some(ast::Expression::Identifier(ast::Identifier::new(self.curr_tkn.clone().into_owned(), value, span))),
+            Token::Int(value, suffix) => Some(ast::Expression::Integer(ast::IntegerLiteral::new(value, suffix, span))),
+            Token::Float(value, suffix) => Some(ast::Expression::Float(ast::FloatLiteral::new(value, suffix, span))),
+            Token::True => Some(ast::Expression::Boolean(ast::BooleanLiteral::new(true, span))),
+            Token::False => Some(ast::Expression::Boolean(ast::BooleanLiteral::new(false, span))),
+            Token::Str(value) => Some(ast::Expression::Str(ast::StringLiteral::new(value.into_owned(), span))),
+            //  the lexer has already reported it
+            Token::Error => None,
+            Token::Null => Some(ast::Expression::Null(ast::NullLiteral::new(span))),
+            Token::LParen => self.parse_grouped(),
+            Token::Function => self.parse_function_literal().map(ast::Expression::Function),
+            Token::If => self.parse_if().map(ast::Expression::If),
+            Token::LSquirly => self.parse_block().map(ast::Expression::Block),
+            Token::Bang | Token::Dash => {
+                let operator = ast::PrefixOperator::from_token(&self.curr_tkn).expect("is a prefix operator");
+                self.next();
+                let right = self.parse_expression(Precedence::Prefix)?;
+                let span = span.to(right.span());
+                Some(ast::Expression::Prefix(ast::PrefixExpression::new(operator, right, span)))
+            },
+            token => {
+                self.errors.push_err(ParserError::ExpressionExpected(token.into_owned()), span);
+                None
+            },
+        };
+    }
+
+    fn parse_infix(&mut self, left: ast::Expression, operator: ast::InfixOperator) -> Option<ast::Expression> {
+        let precedence = Precedence::of(&self.curr_tkn);
+        self.next();
+        let right = self.parse_expression(precedence)?;
+
+        let span = left.span().to(right.span());
+        Some(ast::Expression::Infix(ast::InfixExpression::new(left, operator, right, span)))
+    }
+
+    fn parse_logical(&mut self, left: ast::Expression, operator: ast::LogicalOperator) -> Option<ast::Expression> {
+        let precedence = Precedence::of(&self.curr_tkn);
+        self.next();
+        let right = self.parse_expression(precedence)?;
+
+        let span = left.span().to(right.span());
+        Some(ast::Expression::Logical(ast::LogicalExpression::new(left, operator, right, span)))
+    }
+
+    fn parse_index(&mut self, object: ast::Expression) -> Option<ast::IndexExpression> {
         self.next();
+        let index = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(&Token::RBracket) {
+            self.errors.push_err(ParserError::PeekError(Token::RBracket, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        let span = object.span().to(self.curr_span);
+        Some(ast::IndexExpression::new(object, index, span))
+    }
+
+    fn parse_field(&mut self, object: ast::Expression) -> Option<ast::FieldExpression> {
+        if !self.expect_peek(&Token::Ident("".into())) {
+            self.errors.push_err(ParserError::IdentifierExpected, self.peek_span);
+            return None;
+        }
+
+        let field = match self.curr_tkn.clone() {
+            Token::Ident(value) => ast::Identifier::new(self.curr_tkn.clone().into_owned(), value, self.curr_span),
+            _ => unreachable!(),
+        };
+
+        let span = object.span().to(self.curr_span);
+        Some(ast::FieldExpression::new(object, field, span))
+    }
+
+    fn parse_range(&mut self, start: ast::Expression) -> Option<ast::Expression> {
+        let inclusive = self.curr_tok_is(&Token::DotDotEq);
+        self.next();
+        let end = self.parse_expression(Precedence::Range)?;
+
+        let span = start.span().to(end.span());
+        Some(ast::Expression::Range(ast::RangeExpression::new(start, end, inclusive, span)))
+    }
+
+    fn parse_call(&mut self, function: ast::Expression) -> Option<ast::CallExpression> {
+        let mut arguments = Vec::new();
+
+        if self.peek_tok_is(&Token::RParen) {
+            self.next();
+            let span = function.span().to(self.curr_span);
+            return Some(ast::CallExpression::new(function, arguments, span));
+        }
+
+        loop {
+            self.next();
+            arguments.push(self.parse_expression(Precedence::Lowest)?);
+
+            if self.peek_tok_is(&Token::RParen) {
+                self.next();
+                break;
+            }
+
+            if !self.peek_tok_is(&Token::Comma) {
+                self.errors.push_err(ParserError::UnclosedArguments(self.peek_tkn.clone().into_owned()), self.peek_span);
+                return None;
+            }
+            self.next();
+
+            if self.peek_tok_is(&Token::RParen) {
+                self.errors.push_err(ParserError::TrailingComma, self.curr_span);
+                return None;
+            }
+        }
+
+        let span = function.span().to(self.curr_span);
+        Some(ast::CallExpression::new(function, arguments, span))
+    }
+
+    fn parse_grouped(&mut self) -> Option<ast::Expression> {
+        self.next();
+        let expr = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(&Token::RParen) {
+            self.errors.push_err(ParserError::PeekError(Token::RParen, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        Some(expr)
+    }
+
+    fn parse_function_literal(&mut self) -> Option<ast::FunctionLiteral> {
+        let start = self.curr_span;
+
+        if !self.expect_peek(&Token::LParen) {
+            self.errors.push_err(ParserError::PeekError(Token::LParen, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
 
-        //  TODO: Do expressions, currently skipping until semicolon is encountered
-        while !self.curr_tok_is(&Token::Semicolon) {
+        let parameters = self.parse_parameters()?;
+
+        let mut return_type = None;
+        if self.peek_tok_is(&Token::ReturnOp) {
+            self.next();
             self.next();
+            return_type = Some(self.parse_type()?);
+        }
+
+        if !self.expect_peek(&Token::LSquirly) {
+            self.errors.push_err(ParserError::PeekError(Token::LSquirly, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
         }
 
-        Some(stmt)
+        //  a function body starts outside of any loop, even if the function
+        //  is defined inside one
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.parse_block();
+        self.loop_depth = loop_depth;
+        let body = body?;
+
+        Some(ast::FunctionLiteral::new(parameters, return_type, body, start.to(self.curr_span)))
+    }
+
+    fn parse_parameters(&mut self) -> Option<Vec<ast::Parameter>> {
+        let mut parameters = Vec::new();
+
+        if self.peek_tok_is(&Token::RParen) {
+            self.next();
+            return Some(parameters);
+        }
+
+        loop {
+            if !self.expect_peek(&Token::Ident("".into())) {
+                self.errors.push_err(ParserError::IdentifierExpected, self.peek_span);
+                return None;
+            }
+
+            let start = self.curr_span;
+            let name = match self.curr_tkn.clone() {
+                Token::Ident(value) => ast::Identifier::new(self.curr_tkn.clone().into_owned(), value, self.curr_span),
+                _ => unreachable!(),
+            };
+
+            if !self.expect_peek(&Token::Colon) {
+                self.errors.push_err(ParserError::PeekError(Token::Colon, self.peek_tkn.clone().into_owned()), self.peek_span);
+                return None;
+            }
+
+            self.next();
+            let param_type = self.parse_type()?;
+            parameters.push(ast::Parameter::new(name, param_type, start.to(self.curr_span)));
+
+            if !self.peek_tok_is(&Token::Comma) {
+                break;
+            }
+            self.next();
+        }
+
+        if !self.expect_peek(&Token::RParen) {
+            self.errors.push_err(ParserError::PeekError(Token::RParen, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        Some(parameters)
+    }
+
+    fn parse_type(&mut self) -> Option<ast::TypeExpr> {
+        let start = self.curr_span;
+        return match self.curr_tkn {
+            Token::Bool | Token::I8 | Token::I16 | Token::I32 | Token::I64
+                | Token::F8 | Token::F16 | Token::F32 | Token::F64
+                | Token::U8 | Token::StringTok => {
+                Some(ast::TypeExpr::Primitive(ast::PrimitiveType::new(self.curr_tkn.clone().into_owned(), start)))
+            },
+            Token::QMark => {
+                self.next();
+                let inner = self.parse_type()?;
+                let span = start.to(inner.span());
+                Some(ast::TypeExpr::Optional(ast::OptionalType::new(inner, span)))
+            },
+            Token::LBracket => {
+                let mut length = None;
+                if let Token::Int(value, _) = self.peek_tkn {
+                    self.next();
+                    length = Some(value);
+
+                    if !self.expect_peek(&Token::RBracket) {
+                        self.errors.push_err(ParserError::PeekError(Token::RBracket, self.peek_tkn.clone().into_owned()), self.peek_span);
+                        return None;
+                    }
+                }
+
+                self.next();
+                let element = self.parse_type()?;
+
+                if length.is_none() && !self.expect_peek(&Token::RBracket) {
+                    self.errors.push_err(ParserError::PeekError(Token::RBracket, self.peek_tkn.clone().into_owned()), self.peek_span);
+                    return None;
+                }
+
+                Some(ast::TypeExpr::Array(ast::ArrayType::new(element, length, start.to(self.curr_span))))
+            },
+            Token::Function => {
+                if !self.expect_peek(&Token::LParen) {
+                    self.errors.push_err(ParserError::PeekError(Token::LParen, self.peek_tkn.clone().into_owned()), self.peek_span);
+                    return None;
+                }
+
+                let mut parameters = Vec::new();
+                while !self.peek_tok_is(&Token::RParen) {
+                    self.next();
+                    parameters.push(self.parse_type()?);
+
+                    if !self.peek_tok_is(&Token::Comma) {
+                        break;
+                    }
+                    self.next();
+                }
+
+                if !self.expect_peek(&Token::RParen) {
+                    self.errors.push_err(ParserError::PeekError(Token::RParen, self.peek_tkn.clone().into_owned()), self.peek_span);
+                    return None;
+                }
+
+                let mut return_type = None;
+                if self.peek_tok_is(&Token::ReturnOp) {
+                    self.next();
+                    self.next();
+                    return_type = Some(self.parse_type()?);
+                }
+
+                Some(ast::TypeExpr::Function(ast::FunctionType::new(parameters, return_type, start.to(self.curr_span))))
+            },
+            _ => {
+                self.errors.push_err(ParserError::TypeExpected(self.curr_tkn.clone().into_owned()), self.curr_span);
+                None
+            },
+        };
+    }
+
+    fn parse_block(&mut self) -> Option<ast::BlockStatement> {
+        let start = self.curr_span;
+        let mut statements = Vec::new();
+        let mut tail = None;
+        self.next();
+
+        while !self.curr_tok_is(&Token::RSquirly) {
+            if self.curr_tok_is(&Token::Eof) {
+                self.errors.push_err(ParserError::PeekError(Token::RSquirly, Token::Eof), self.curr_span);
+                return None;
+            }
+
+            let stmt = match self.curr_tkn {
+                Token::Var | Token::Const | Token::Return | Token::DocComment(_)
+                | Token::While | Token::For | Token::Break | Token::Continue => self.parse_statement(),
+                _ => match self.parse_statement_or_tail(true) {
+                    Some(StatementOrTail::Tail(expr)) => {
+                        tail = Some(expr);
+                        self.next();
+                        continue;
+                    },
+                    Some(StatementOrTail::Statement(stmt)) => Some(stmt),
+                    None => None,
+                },
+            };
+
+            match stmt {
+                Some(stmt) => {
+                    statements.push(stmt);
+                    self.next();
+                },
+                None => self.synchronize(),
+            }
+        }
+
+        Some(ast::BlockStatement::new(statements, tail, start.to(self.curr_span)))
+    }
+
+    fn parse_if(&mut self) -> Option<ast::IfExpression> {
+        let start = self.curr_span;
+        self.next();
+        let condition = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(&Token::LSquirly) {
+            self.errors.push_err(ParserError::PeekError(Token::LSquirly, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        let consequence = self.parse_block()?;
+
+        let mut alternative = None;
+        if self.peek_tok_is(&Token::Else) {
+            self.next();
+
+            if self.expect_peek(&Token::If) {
+                alternative = Some(ast::Expression::If(self.parse_if()?));
+            } else if self.expect_peek(&Token::LSquirly) {
+                alternative = Some(ast::Expression::Block(self.parse_block()?));
+            } else {
+                self.errors.push_err(ParserError::PeekError(Token::LSquirly, self.peek_tkn.clone().into_owned()), self.peek_span);
+                return None;
+            }
+        }
+
+        Some(ast::IfExpression::new(condition, consequence, alternative, start.to(self.curr_span)))
+    }
+
+    /// Parses an expression used as a statement, or an assignment when the
+    /// expression is followed by `=` or a compound assignment operator.
+    fn parse_expression_stmt(&mut self) -> Option<ast::Statement> {
+        return match self.parse_statement_or_tail(false)? {
+            StatementOrTail::Statement(stmt) => Some(stmt),
+            StatementOrTail::Tail(_) => unreachable!("tails are only parsed in blocks"),
+        };
+    }
+
+    /// Parses a statement that starts with an expression: an assignment, or
+    /// an expression that ends with `;` unless it's block-like. Inside a block,
+    /// `allow_tail` lets an expression directly before the `}` be the tail.
+    fn parse_statement_or_tail(&mut self, allow_tail: bool) -> Option<StatementOrTail> {
+        let expr = match self.curr_tkn {
+            //  as in Rust, an `if` or block starting a statement is the whole
+            //  statement, so what follows it isn't read as an operand
+            Token::If | Token::LSquirly => self.parse_prefix()?,
+            _ => self.parse_expression(Precedence::Lowest)?,
+        };
+
+        if is_assign_op(&self.peek_tkn) {
+            self.next();
+            return self.parse_assign_stmt(expr).map(StatementOrTail::Statement);
+        }
+
+        if allow_tail && self.peek_tok_is(&Token::RSquirly) {
+            return Some(StatementOrTail::Tail(expr));
+        }
+
+        if self.peek_tok_is(&Token::Semicolon) {
+            self.next();
+        } else if !expr.is_block_like() {
+            self.errors.push_err(ParserError::PeekError(Token::Semicolon, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        Some(StatementOrTail::Statement(ast::Statement::Expression(expr)))
+    }
+
+    /// Parses the rest of an assignment, with the current token being the
+    /// assignment operator that follows `target`.
+    fn parse_assign_stmt(&mut self, target: ast::Expression) -> Option<ast::Statement> {
+        let operator = ast::InfixOperator::from_compound_assign(&self.curr_tkn);
+        let is_place = target.is_place();
+        if !is_place {
+            self.errors.push_err(ParserError::InvalidAssignTarget, target.span());
+        }
+
+        self.next();
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(&Token::Semicolon) {
+            self.errors.push_err(ParserError::PeekError(Token::Semicolon, self.peek_tkn.clone().into_owned()), self.peek_span);
+            return None;
+        }
+
+        if !is_place {
+            return None;
+        }
+
+        let span = target.span().to(self.curr_span);
+        Some(ast::Statement::Assign(ast::AssignStatement::new(target, operator, value, span)))
+    }
+
+    /// Skips tokens after a syntax error until the start of the next
+    /// statement, so that one mistake doesn't cascade into more errors.
+    ///
+    /// Stops after a `;`, before a statement keyword, or before the `}` that
+    /// closes the enclosing block, skipping over any nested blocks.
+    fn synchronize(&mut self) {
+        let mut depth: usize = 0;
+
+        loop {
+            match self.curr_tkn {
+                Token::Eof => return,
+                Token::Semicolon if depth == 0 => {
+                    self.next();
+                    return;
+                },
+                Token::RSquirly if depth == 0 => return,
+                Token::RSquirly => depth -= 1,
+                Token::LSquirly => depth += 1,
+                _ => {},
+            }
+
+            self.next();
+
+            if depth == 0 && matches!(self.curr_tkn, Token::Var | Token::Const | Token::Return | Token::If | Token::While | Token::For) {
+                return;
+            }
+        }
     }
 
     fn curr_tok_is(&self, token: &Token) -> bool {
         match self.curr_tkn {
             Token::Ident(_) => matches!(token, Token::Ident(_)),
-            Token::Int(_) => matches!(token, Token::Int(_)),
-            Token::Float(_) => matches!(token, Token::Float(_)),
+            Token::Int(..) => matches!(token, Token::Int(..)),
+            Token::Float(..) => matches!(token, Token::Float(..)),
             _ => &self.curr_tkn == token
         }
     }
@@ -99,8 +776,8 @@ impl Parser {
     fn peek_tok_is(&self, token: &Token) -> bool {
         match self.peek_tkn {
             Token::Ident(_) => matches!(token, Token::Ident(_)),
-            Token::Int(_) => matches!(token, Token::Int(_)),
-            Token::Float(_) => matches!(token, Token::Float(_)),
+            Token::Int(..) => matches!(token, Token::Int(..)),
+            Token::Float(..) => matches!(token, Token::Float(..)),
             _ => &self.peek_tkn == token
         }
     }
@@ -115,36 +792,55 @@ impl Parser {
     }
 }
 
+/// A statement starting with an expression, or the tail expression of a block.
+//  unpacked as soon as it's returned, so boxing the statement would only add
+//  an allocation
+#[allow(clippy::large_enum_variant)]
+enum StatementOrTail {
+    Statement(ast::Statement),
+    Tail(ast::Expression),
+}
+
+fn is_assign_op(token: &Token) -> bool {
+    *token == Token::Assign || ast::InfixOperator::from_compound_assign(token).is_some()
+}
+
 #[test]
 fn parse_let_statements_test() {
     let input = r#"
         const x = 7;
         var y = 5.4;
         "#;
-    let lexer = Lexer::new(input.to_string());
+    let lexer = Lexer::new(input);
     let mut parser = Parser::new(lexer);
 
     let program = parser.parse_program();
 
-    println!("{program}");
-
-    assert_eq!(parser.errors.len(), 0);
+    assert_eq!(parser.errors.errors.len(), 0);
 
     if program.statements.len() != 2 {
         panic!("Incorrect number of statements");
     }
 
     let statements: Vec<ast::Statement> = vec![
-        ast::Statement::Let(ast::LetStatement::new(Token::Const, ast::Identifier::new(Token::Ident(String::from("x")), String::from("x")))),
-        ast::Statement::Let(ast::LetStatement::new(Token::Var, ast::Identifier::new(Token::Ident(String::from("y")), String::from("y")))),
+        ast::Statement::Let(ast::LetStatement::new(
+            Token::Const,
+            ast::Identifier::new(Token::Ident("x".into()), "x".into(), Span::default()),
+            None,
+            ast::Expression::Integer(ast::IntegerLiteral::new(7, None, Span::default())),
+            Span::default(),
+        )),
+        ast::Statement::Let(ast::LetStatement::new(
+            Token::Var,
+            ast::Identifier::new(Token::Ident("y".into()), "y".into(), Span::default()),
+            None,
+            ast::Expression::Float(ast::FloatLiteral::new(5.4, None, Span::default())),
+            Span::default(),
+        )),
     ];
 
-    let mut i: usize = 0;
-    for statement in statements {
+    for (i, statement) in statements.into_iter().enumerate() {
         let got = program.statements.get(i);
-        i += 1;
-
-        //println!("expected: {}, got: {}", statement, got.unwrap());
         assert_eq!(&statement, got.unwrap());
     }
 }
@@ -155,31 +851,433 @@ fn parse_return_statements_test() {
         return 5;
         return true;
         "#;
-    let lexer = Lexer::new(input.to_string());
+    let lexer = Lexer::new(input);
     let mut parser = Parser::new(lexer);
 
     let program = parser.parse_program();
 
-    println!("{program}");
-
-    assert_eq!(parser.errors.len(), 0);
+    assert_eq!(parser.errors.errors.len(), 0);
 
     if program.statements.len() != 2 {
         panic!("Incorrect number of statements");
     }
 
     let statements: Vec<ast::Statement> = vec![
-        ast::Statement::Return(ast::ReturnStatement::new()),
-        ast::Statement::Return(ast::ReturnStatement::new()),
+        ast::Statement::Return(ast::ReturnStatement::new(ast::Expression::Integer(ast::IntegerLiteral::new(5, None, Span::default())), Span::default())),
+        ast::Statement::Return(ast::ReturnStatement::new(ast::Expression::Boolean(ast::BooleanLiteral::new(true, Span::default())), Span::default())),
     ];
 
-    let mut i: usize = 0;
-    for statement in statements {
+    for (i, statement) in statements.into_iter().enumerate() {
         let got = program.statements.get(i);
-        i += 1;
-
-        //println!("expected: {}, got: {}", statement, got.unwrap());
         assert_eq!(&statement, got.unwrap());
     }
 }
 
+#[test]
+fn operator_precedence_test() {
+    let tests = vec![
+        ("const a = -b;", "const a = (-b);"),
+        ("const a = !true;", "const a = (!true);"),
+        ("const a = !-b;", "const a = (!(-b));"),
+        ("const a = b + c + d;", "const a = ((b + c) + d);"),
+        ("const a = b + c * d;", "const a = (b + (c * d));"),
+        ("const a = b * c - d / e;", "const a = ((b * c) - (d / e));"),
+        ("const a = -b * c;", "const a = ((-b) * c);"),
+        ("const a = b + c == d * e;", "const a = ((b + c) == (d * e));"),
+        ("const a = b != c == false;", "const a = ((b != c) == false);"),
+        ("const a = (b + c) * d;", "const a = ((b + c) * d);"),
+        ("const a = -(b + c);", "const a = (-(b + c));"),
+        ("return x == null;", "return (x == null);"),
+        ("const a = b < c == d >= e;", "const a = ((b < c) == (d >= e));"),
+        ("const a = b || c && d;", "const a = (b || (c && d));"),
+        ("const a = b && c || d && e;", "const a = ((b && c) || (d && e));"),
+        ("const a = !b && c == d;", "const a = ((!b) && (c == d));"),
+        ("const a = b | c ^ d & e;", "const a = (b | (c ^ (d & e)));"),
+        ("const a = b << 1 + c;", "const a = (b << (1 + c));"),
+        ("const a = b & 1 == 0;", "const a = ((b & 1) == 0);"),
+        ("const a = b % c * d >> e;", "const a = (((b % c) * d) >> e);"),
+        ("const a = b <= c || d > e;", "const a = ((b <= c) || (d > e));"),
+        ("const a = 0xff + 1_000i64 * 2.5e1f32;", "const a = (255 + (1000i64 * 25.0f32));"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+}
+
+#[test]
+fn node_spans_test() {
+    let input = "const x = 1 + -y;\nreturn x;";
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let program = parser.parse_program();
+
+    assert_eq!(parser.errors.errors.len(), 0);
+    assert_eq!(program.span, Span::new(Default::default(), 0, input.len(), 1, 1));
+
+    let ast::Statement::Let(stmt) = &program.statements[0] else { panic!("Expected a let statement") };
+    assert_eq!(stmt.span, Span::new(Default::default(), 0, 17, 1, 1));
+    assert_eq!(stmt.name.span, Span::new(Default::default(), 6, 7, 1, 7));
+    assert_eq!(stmt.value.span(), Span::new(Default::default(), 10, 16, 1, 11));
+
+    let ast::Expression::Infix(infix) = &stmt.value else { panic!("Expected an infix expression") };
+    assert_eq!(infix.right.span(), Span::new(Default::default(), 14, 16, 1, 15));
+
+    let ast::Statement::Return(stmt) = &program.statements[1] else { panic!("Expected a return statement") };
+    assert_eq!(stmt.span, Span::new(Default::default(), 18, 27, 2, 1));
+}
+
+#[test]
+fn function_literal_test() {
+    let tests = vec![
+        ("const add = fn(x: i32, y: i32) -> i32 { return x + y; }", "const add = fn(x: i32, y: i32) -> i32 { return (x + y); };"),
+        ("const f = fn() { return 1; };", "const f = fn() { return 1; };"),
+        ("const g = fn(s: String) {};", "const g = fn(s: String) { };"),
+        ("const h = fn(b: bool) -> f64 { const y = 2.0; return y; }", "const h = fn(b: bool) -> f64 { const y = 2.0; return y; };"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+
+    let mut parser = Parser::new(Lexer::new("const f = fn(x i32) {};"));
+    parser.parse_program();
+    assert!(matches!(parser.errors.errors[0].0, ParserError::PeekError(Token::Colon, Token::I32)));
+}
+
+#[test]
+fn block_like_initializer_test() {
+    let input = r#"
+        const f = fn() -> i32 { return 1; }
+        -f();
+        const g = if true { 1 } else { 2 }
+        (g);
+        const h = fn() {}
+        -h;
+        const i = if true { 1 } else { 2 } + 3;
+        "#;
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+
+    assert!(parser.errors.errors.is_empty(), "{}", parser.errors);
+    assert_eq!(program.to_string(), "\
+const f = fn() -> i32 { return 1; };
+(-f());
+const g = if true { 1 } else { 2 };
+g;
+const h = fn() { };
+(-h);
+const i = (if true { 1 } else { 2 } + 3);
+");
+}
+
+#[test]
+fn block_like_statement_test() {
+    let input = r#"
+        const f = fn(x: i32) -> i32 {
+            if true { x } else { 2 }
+            -x;
+            if x > 1 { x } else { 2 } (x);
+            { x }
+            -x
+        };
+        if true { 1 } else { 2 }
+        -f(1);
+        "#;
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+
+    assert!(parser.errors.errors.is_empty(), "{}", parser.errors);
+    assert_eq!(program.to_string(), "\
+const f = fn(x: i32) -> i32 { if true { x } else { 2 } (-x); if (x > 1) { x } else { 2 } x; { x } (-x) };
+if true { 1 } else { 2 }
+(-f(1));
+");
+}
+
+#[test]
+fn call_expression_test() {
+    let tests = vec![
+        ("const sum = add(x, y);", "const sum = add(x, y);"),
+        ("const a = f();", "const a = f();"),
+        ("const a = add(1, 2 * 3, -b);", "const a = add(1, (2 * 3), (-b));"),
+        ("const a = a + add(b * c) + d;", "const a = ((a + add((b * c))) + d);"),
+        ("const a = add(a, b, 1, 2 * 3, 4 + 5, add(6, 7 * 8));", "const a = add(a, b, 1, (2 * 3), (4 + 5), add(6, (7 * 8)));"),
+        ("const a = f(a)(b);", "const a = f(a)(b);"),
+        ("const a = -f(b);", "const a = (-f(b));"),
+        ("const a = fn(x: i32) -> i32 { return x; }(5);", "const a = fn(x: i32) -> i32 { return x; }(5);"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+
+    let mut parser = Parser::new(Lexer::new("const a = add(x, y;"));
+    parser.parse_program();
+    assert!(matches!(parser.errors.errors[0].0, ParserError::UnclosedArguments(Token::Semicolon)));
+
+    let mut parser = Parser::new(Lexer::new("const a = add(x, y,);"));
+    parser.parse_program();
+    assert!(matches!(parser.errors.errors[0].0, ParserError::TrailingComma));
+}
+
+#[test]
+fn type_annotation_test() {
+    let tests = vec![
+        ("const x: i32 = 0;", "const x: i32 = 0;"),
+        ("var z: ?u8 = null;", "var z: ?u8 = null;"),
+        ("var s: [String] = y;", "var s: [String] = y;"),
+        ("var s: [4]f32 = y;", "var s: [4]f32 = y;"),
+        ("var s: ?[2][?bool] = y;", "var s: ?[2][?bool] = y;"),
+        ("const f: fn(i32, [u8]) -> ?i64 = g;", "const f: fn(i32, [u8]) -> ?i64 = g;"),
+        ("const f: fn() = g;", "const f: fn() = g;"),
+        ("const f = fn(cb: fn(i8) -> bool, xs: [8]i8) -> ?i8 { return null; }", "const f = fn(cb: fn(i8) -> bool, xs: [8]i8) -> ?i8 { return null; };"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{input}: {}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+}
+
+#[test]
+fn if_expression_test() {
+    let tests = vec![
+        ("if (x != y) { x; }", "if (x != y) { x; }"),
+        ("if x { y } else { z }", "if x { y } else { z }"),
+        ("if a { 1 } else if b { 2 } else { 3 }", "if a { 1 } else if b { 2 } else { 3 }"),
+        ("const m = if a == b { a } else { b };", "const m = if (a == b) { a } else { b };"),
+        ("const v = { const t = 2; t * 3 };", "const v = { const t = 2; (t * 3) };"),
+        ("const f = fn() -> i32 { if c { return 1; } 2 }", "const f = fn() -> i32 { if c { return 1; } 2 };"),
+        ("{ if a { b } else { c } }", "{ if a { b } else { c } }"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{input}: {}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+
+    let mut parser = Parser::new(Lexer::new("if a { b } else c"));
+    parser.parse_program();
+    assert!(matches!(parser.errors.errors[0].0, ParserError::PeekError(Token::LSquirly, _)));
+}
+
+#[test]
+fn loop_test() {
+    let tests = vec![
+        ("while x < 10 { x; }", "while (x < 10) { x; }"),
+        ("for i in 0..n + 1 { f(i); }", "for i in (0..(n + 1)) { f(i); }"),
+        ("for i in a..=b { if i == c { break; } continue; }", "for i in (a..=b) { if (i == c) { break; } continue; }"),
+        ("const f = fn() { while a { for b in c { break; } continue; } };", "const f = fn() { while a { for b in c { break; } continue; } };"),
+        ("const r = 1..2;", "const r = (1..2);"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{input}: {}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+
+    let input = r#"
+        break;
+        while a {
+            const f = fn() { continue; };
+            { break; }
+        }
+        for x y { }
+        "#;
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+
+    let errors: Vec<String> = parser.errors.errors.iter().map(|(err, span)| format!("{}: {err}", span.line)).collect();
+    assert_eq!(errors, vec![
+        "2: `break` outside of a loop",
+        "4: `continue` outside of a loop",
+        "7: Expected: in, Got: Ident(y) instead",
+    ]);
+    assert_eq!(program.statements.len(), 2);
+}
+
+#[test]
+fn assignment_test() {
+    let tests = vec![
+        ("x = y;", "x = y;"),
+        ("x += 1;", "x += 1;"),
+        ("a[i] = b.c;", "a[i] = b.c;"),
+        ("a.b.c <<= f(x)[0] * 2;", "a.b.c <<= (f(x)[0] * 2);"),
+        ("print(x);", "print(x);"),
+        ("-x.y[1];", "(-x.y[1]);"),
+        ("if (x != y) { x = y; }", "if (x != y) { x = y; }"),
+        ("const f = fn() { total %= n; total };", "const f = fn() { total %= n; total };"),
+    ];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let program = parser.parse_program();
+
+        assert_eq!(parser.errors.errors.len(), 0, "{input}: {}", parser.errors);
+        assert_eq!(program.to_string(), format!("{expected}\n"));
+    }
+
+    let input = r#"
+        f(a) = 1;
+        x + 1 = 2;
+        y = 3;
+        z
+        "#;
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+
+    let errors: Vec<String> = parser.errors.errors.iter().map(|(err, span)| format!("{span}: {err}")).collect();
+    assert_eq!(errors, vec![
+        "2:9: Invalid left-hand side of assignment",
+        "3:9: Invalid left-hand side of assignment",
+        "6:9: Expected: Semicolon, Got: Eof instead",
+    ]);
+    assert_eq!(program.to_string(), "y = 3;\n");
+}
+
+#[test]
+fn error_recovery_test() {
+    let input = r#"
+        const a = 1 +;
+        const b = 2;
+        const f = fn(x i32) {
+            return x;
+        }
+        var c: = 3;
+        const g = fn() {
+            const d = (1;
+            return 4;
+        }
+        }
+        return 5
+        const e = 6;
+        "#;
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let program = parser.parse_program();
+
+    let errors: Vec<String> = parser.errors.errors.iter().map(|(err, span)| format!("{}: {err}", span.line)).collect();
+    assert_eq!(errors, vec![
+        "2: Expected an expression, Got: Semicolon instead",
+        "4: Expected: Colon, Got: i32 instead",
+        "7: Expected a type, Got: Assign instead",
+        "9: Expected: RParen, Got: Semicolon instead",
+        "12: Unexpected RSquirly",
+        "14: Expected: Semicolon, Got: const instead",
+    ]);
+
+    assert_eq!(program.to_string(), "const b = 2;\nconst g = fn() { return 4; };\nconst e = 6;\n");
+}
+
+#[test]
+fn string_literal_test() {
+    let input = r#"const s: String = "a\tb";
+const r = r"c:\path";
+const e = "\x";
+const t = "open
+"#;
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let program = parser.parse_program();
+
+    assert_eq!(program.to_string(), "const s: String = \"a\\tb\";\nconst r = \"c:\\\\path\";\n");
+    assert!(parser.errors.errors.is_empty());
+    let errors: Vec<String> = parser.lex_errors().iter().map(|err| err.to_string()).collect();
+    assert_eq!(errors, vec![
+        "3:12: Invalid escape sequence: \\x",
+        "4:11: Unterminated string literal",
+    ]);
+}
+
+#[test]
+fn doc_comment_test() {
+    let input = r#"
+        /// The answer.
+        /// Computed slowly.
+        const x: i32 = 42; // not a doc comment
+        const f = fn() {
+            /// A local.
+            var y = x;
+            /// Dangling.
+        }
+        "#;
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let program = parser.parse_program();
+
+    let ast::Statement::Let(x) = &program.statements[0] else { panic!("Expected a let statement") };
+    assert_eq!(x.docs, vec!["The answer.", "Computed slowly."]);
+
+    let ast::Statement::Let(f) = &program.statements[1] else { panic!("Expected a let statement") };
+    let ast::Expression::Function(function) = &f.value else { panic!("Expected a function literal") };
+    let ast::Statement::Let(y) = &function.body.statements[0] else { panic!("Expected a let statement") };
+    assert_eq!(y.docs, vec!["A local."]);
+
+    assert_eq!(parser.errors.errors.len(), 1);
+    assert!(matches!(parser.errors.errors[0].0, ParserError::DanglingDocComment));
+    assert_eq!(parser.errors.errors[0].1.line, 8);
+}
+
+#[test]
+fn parse_file_test() {
+    use crate::source::SourceMap;
+
+    let mut map = SourceMap::new();
+    let file = map.add_file(String::from("test.ind"), include_str!("../../test.ind").to_string());
+    let mut parser = Parser::new(Lexer::for_file(file, &map.get(file).src));
+
+    let program = parser.parse_program();
+
+    assert!(parser.lex_errors().is_empty());
+    assert!(parser.errors().errors.is_empty(), "{}", parser.errors());
+    assert_eq!(program.statements.len(), 5);
+
+    let names: Vec<String> = program.statements.iter().map(|statement| match statement {
+        ast::Statement::Let(stmt) => {
+            assert_eq!(stmt.span.file, file);
+            stmt.name.to_string()
+        },
+        _ => panic!("Expected a let statement, got {statement}"),
+    }).collect();
+    assert_eq!(names, vec!["x", "y", "add", "sum", "z"]);
+}
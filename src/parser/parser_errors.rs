@@ -1,11 +1,13 @@
 use std::error::Error;
 use std::fmt::{ Display, Formatter };
 
+use crate::diagnostics::{ Diagnostic, Label, ToDiagnostic };
 use crate::lexer::Token;
+use crate::source::Span;
 
 #[derive(Debug, Default)]
 pub struct ParserErrors {
-    pub errors: Vec<ParserError>,
+    pub errors: Vec<(ParserError, Span)>,
 }
 
 impl Error for ParserErrors {
@@ -14,37 +16,42 @@ impl Error for ParserErrors {
 impl Display for ParserErrors {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { 
         writeln!(f, "Parser errors:")?;
-        for err in &self.errors {
-            writeln!(f, "\t{err}")?;
+        for (err, span) in &self.errors {
+            writeln!(f, "\t{span}: {err}")?;
         }
         Ok(())
     }
 }
 
 impl ParserErrors {
-    pub fn new() -> ParserErrors {
-        ParserErrors {
-            errors: Vec::new(),
+    /// Records an error, unless it is about a `Token::Error` the lexer has
+    /// already reported.
+    pub fn push_err(&mut self, err: ParserError, span: Span) {
+        if err.got() == Some(&Token::Error) {
+            return;
         }
+        self.errors.push((err, span));
     }
 
-    pub fn push_err(&mut self, err: ParserError) {
-        self.errors.push(err);
-    }
-
-    pub fn append_errs(&mut self, mut errors: Vec<ParserError>) {
-        self.errors.append(&mut errors);
-    }
-
-    pub fn len(&self) -> usize {
-        self.errors.len()
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors.iter()
+            .map(|(err, span)| err.to_diagnostic().with_label(Label::primary(*span, err.label())))
+            .collect()
     }
 }
 
 #[derive(Debug)]
 pub enum ParserError {
     IdentifierExpected,
-    PeekError(Token, Token),
+    PeekError(Token<'static>, Token<'static>),
+    ExpressionExpected(Token<'static>),
+    TypeExpected(Token<'static>),
+    UnclosedArguments(Token<'static>),
+    TrailingComma,
+    Unexpected(Token<'static>),
+    DanglingDocComment,
+    OutsideLoop(Token<'static>),
+    InvalidAssignTarget,
 }
 
 impl Display for ParserError {
@@ -52,7 +59,58 @@ impl Display for ParserError {
         match self {
             ParserError::IdentifierExpected => write!(f, "Identifier expected"),
             ParserError::PeekError(expected, got) => write!(f, "Expected: {}, Got: {} instead", expected, got),
+            ParserError::ExpressionExpected(got) => write!(f, "Expected an expression, Got: {} instead", got),
+            ParserError::TypeExpected(got) => write!(f, "Expected a type, Got: {} instead", got),
+            ParserError::UnclosedArguments(got) => write!(f, "Expected: Comma or RParen after argument, Got: {} instead", got),
+            ParserError::TrailingComma => write!(f, "Trailing comma in argument list"),
+            ParserError::Unexpected(got) => write!(f, "Unexpected {}", got),
+            ParserError::DanglingDocComment => write!(f, "Doc comment is not followed by a declaration"),
+            ParserError::OutsideLoop(keyword) => write!(f, "`{}` outside of a loop", keyword),
+            ParserError::InvalidAssignTarget => write!(f, "Invalid left-hand side of assignment"),
         }
     }
 }
 
+impl ToDiagnostic for ParserError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string());
+        return match self {
+            ParserError::PeekError(Token::Semicolon, _) => diagnostic.with_help("statements must be terminated with `;`"),
+            ParserError::UnclosedArguments(_) => diagnostic.with_help("close the argument list with `)`"),
+            ParserError::TrailingComma => diagnostic.with_help("remove the trailing comma"),
+            ParserError::DanglingDocComment => diagnostic.with_help("use `//` for a regular comment"),
+            ParserError::OutsideLoop(_) => diagnostic.with_note("`break` and `continue` can only be used inside `while` and `for` loops"),
+            ParserError::InvalidAssignTarget => diagnostic.with_note("only variables, indexes such as `a[i]` and fields such as `a.b` can be assigned to"),
+            _ => diagnostic,
+        };
+    }
+}
+
+impl ParserError {
+    /// The token that was found instead of what the parser expected.
+    fn got(&self) -> Option<&Token<'static>> {
+        return match self {
+            ParserError::PeekError(_, got)
+            | ParserError::ExpressionExpected(got)
+            | ParserError::TypeExpected(got)
+            | ParserError::UnclosedArguments(got)
+            | ParserError::Unexpected(got) => Some(got),
+            _ => None,
+        };
+    }
+
+    fn label(&self) -> String {
+        return match self {
+            ParserError::IdentifierExpected => String::from("expected an identifier here"),
+            ParserError::PeekError(expected, _) => format!("expected {} here", expected),
+            ParserError::ExpressionExpected(_) => String::from("expected an expression here"),
+            ParserError::TypeExpected(_) => String::from("expected a type here"),
+            ParserError::UnclosedArguments(_) => String::from("expected `,` or `)` here"),
+            ParserError::TrailingComma => String::from("trailing comma"),
+            ParserError::Unexpected(_) => String::from("unexpected token"),
+            ParserError::DanglingDocComment => String::from("doc comment does not document anything"),
+            ParserError::OutsideLoop(keyword) => format!("cannot `{}` outside of a loop", keyword),
+            ParserError::InvalidAssignTarget => String::from("cannot assign to this expression"),
+        };
+    }
+}
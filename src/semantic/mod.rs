@@ -0,0 +1,5 @@
+pub mod mutability;
+pub mod resolver;
+pub mod semantic_errors;
+pub mod type_checker;
+pub mod types;
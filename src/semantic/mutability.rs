@@ -0,0 +1,179 @@
+use crate::parser::ast;
+use crate::semantic::resolver::{ DeclKind, Resolution };
+use crate::semantic::semantic_errors::{ SemanticErrors, SemanticError };
+
+/// Rejects assignments to bindings that can't change: `const` declarations
+/// and function parameters.
+///
+/// Runs over a resolved program. Assigning to an element or field counts as
+/// assigning to the binding it's reached through, so `a[0] = 1` needs `a` to
+/// be a `var`. Targets that didn't resolve have already been reported and are
+/// skipped.
+pub struct MutabilityChecker<'r> {
+    resolution: &'r Resolution,
+    errors: SemanticErrors,
+}
+
+impl<'r> MutabilityChecker<'r> {
+    pub fn new(resolution: &'r Resolution) -> MutabilityChecker<'r> {
+        MutabilityChecker {
+            resolution,
+            errors: SemanticErrors::new(),
+        }
+    }
+
+    pub fn check_program(mut self, program: &ast::Program) -> SemanticErrors {
+        for statement in &program.statements {
+            self.check_statement(statement);
+        }
+        self.errors
+    }
+
+    fn check_statement(&mut self, statement: &ast::Statement) {
+        match statement {
+            ast::Statement::Let(stmt) => self.check_expression(&stmt.value),
+            ast::Statement::Return(stmt) => self.check_expression(&stmt.return_value),
+            ast::Statement::Expression(expr) => self.check_expression(expr),
+            ast::Statement::While(stmt) => {
+                self.check_expression(&stmt.condition);
+                self.check_block(&stmt.body);
+            },
+            ast::Statement::For(stmt) => {
+                self.check_expression(&stmt.iterable);
+                self.check_block(&stmt.body);
+            },
+            ast::Statement::Break(_) | ast::Statement::Continue(_) => {},
+            ast::Statement::Assign(stmt) => {
+                self.check_assign(stmt);
+                self.check_expression(&stmt.target);
+                self.check_expression(&stmt.value);
+            },
+        }
+    }
+
+    fn check_block(&mut self, block: &ast::BlockStatement) {
+        for statement in &block.statements {
+            self.check_statement(statement);
+        }
+        if let Some(tail) = &block.tail {
+            self.check_expression(tail);
+        }
+    }
+
+    fn check_expression(&mut self, expression: &ast::Expression) {
+        match expression {
+            ast::Expression::Identifier(_)
+            | ast::Expression::Integer(_)
+            | ast::Expression::Float(_)
+            | ast::Expression::Boolean(_)
+            | ast::Expression::Str(_)
+            | ast::Expression::Null(_) => {},
+            ast::Expression::Prefix(expr) => self.check_expression(&expr.right),
+            ast::Expression::Infix(expr) => {
+                self.check_expression(&expr.left);
+                self.check_expression(&expr.right);
+            },
+            ast::Expression::Logical(expr) => {
+                self.check_expression(&expr.left);
+                self.check_expression(&expr.right);
+            },
+            ast::Expression::Range(expr) => {
+                self.check_expression(&expr.start);
+                self.check_expression(&expr.end);
+            },
+            ast::Expression::Function(expr) => self.check_block(&expr.body),
+            ast::Expression::Call(expr) => {
+                self.check_expression(&expr.function);
+                for argument in &expr.arguments {
+                    self.check_expression(argument);
+                }
+            },
+            ast::Expression::Index(expr) => {
+                self.check_expression(&expr.object);
+                self.check_expression(&expr.index);
+            },
+            ast::Expression::Field(expr) => self.check_expression(&expr.object),
+            ast::Expression::If(expr) => {
+                self.check_expression(&expr.condition);
+                self.check_block(&expr.consequence);
+                if let Some(alternative) = &expr.alternative {
+                    self.check_expression(alternative);
+                }
+            },
+            ast::Expression::Block(expr) => self.check_block(expr),
+        }
+    }
+
+    fn check_assign(&mut self, stmt: &ast::AssignStatement) {
+        let Some(binding) = place_binding(&stmt.target) else {
+            return;
+        };
+        let Some(id) = self.resolution.lookup(binding) else {
+            return;
+        };
+
+        let declaration = self.resolution.declaration(id);
+        if let DeclKind::Const | DeclKind::Parameter = declaration.kind {
+            self.errors.push_err(SemanticError::AssignToImmutable {
+                name: declaration.name,
+                kind: declaration.kind,
+                span: stmt.span,
+                declaration: declaration.span,
+            });
+        }
+    }
+}
+
+/// Returns the binding a place expression is reached through.
+fn place_binding(target: &ast::Expression) -> Option<&ast::Identifier> {
+    return match target {
+        ast::Expression::Identifier(ident) => Some(ident),
+        ast::Expression::Index(expr) => place_binding(&expr.object),
+        ast::Expression::Field(expr) => place_binding(&expr.object),
+        _ => None,
+    };
+}
+
+#[test]
+fn mutability_test() {
+    use crate::lexer::Lexer;
+    use crate::parser::parser::Parser;
+    use crate::semantic::resolver::Resolver;
+
+    let input = r#"
+        const x = 1;
+        var y = 2;
+        x = 3;
+        y = 4;
+        y += x;
+        const inc = fn(n: i32) -> i32 {
+            n += 1;
+            var m = n;
+            m += 1;
+            return m;
+        };
+        for i in 0..10 {
+            x -= i;
+        }
+        const a = y;
+        a[0] = 1;
+        a.b = 2;
+        missing = 5;
+        "#;
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+    assert!(parser.errors().errors.is_empty(), "{}", parser.errors());
+
+    let (resolution, errors) = Resolver::new().resolve_program(&program);
+    assert_eq!(errors.errors.len(), 1);
+    let errors = MutabilityChecker::new(&resolution).check_program(&program);
+
+    let errors: Vec<String> = errors.errors.iter().map(|err| format!("{}: {err}", err.span())).collect();
+    assert_eq!(errors, vec![
+        "4:9: Cannot assign to `x`, which is declared `const`",
+        "8:13: Cannot assign to the parameter `n`",
+        "14:13: Cannot assign to `x`, which is declared `const`",
+        "17:9: Cannot assign to `a`, which is declared `const`",
+        "18:9: Cannot assign to `a`, which is declared `const`",
+    ]);
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::lexer::Token;
+use crate::parser::ast;
+use crate::semantic::semantic_errors::{ SemanticErrors, SemanticError };
+use crate::source::{ Span, Symbol };
+
+/// Identifies a declaration within a `Resolution`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DeclId(pub usize);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DeclKind {
+    Const,
+    Var,
+    Parameter,
+    LoopVariable,
+}
+
+impl Display for DeclKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeclKind::Const => write!(f, "const"),
+            DeclKind::Var => write!(f, "var"),
+            DeclKind::Parameter => write!(f, "parameter"),
+            DeclKind::LoopVariable => write!(f, "loop variable"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Declaration {
+    pub name: Symbol,
+    pub kind: DeclKind,
+    /// The span of the declared name.
+    pub span: Span,
+}
+
+/// The result of name resolution: every declaration in the program, and the
+/// declaration each identifier refers to.
+///
+/// Identifiers are looked up by their span, which covers both the uses of a
+/// name and the name at its declaration.
+#[derive(Debug, Default)]
+pub struct Resolution {
+    pub declarations: Vec<Declaration>,
+    bindings: HashMap<Span, DeclId>,
+}
+
+impl Resolution {
+    pub fn declaration(&self, id: DeclId) -> &Declaration {
+        &self.declarations[id.0]
+    }
+
+    /// Returns the declaration an identifier refers to, or `None` if it
+    /// couldn't be resolved.
+    pub fn lookup(&self, ident: &ast::Identifier) -> Option<DeclId> {
+        self.bindings.get(&ident.span).copied()
+    }
+}
+
+/// Binds identifiers to their declarations, walking the program with a stack
+/// of nested scopes: the program, each function's parameters, each block and
+/// each `for` loop variable.
+///
+/// Declarations are only visible after they've been made, except that a
+/// function literal bound by `const` or `var` can refer to itself.
+pub struct Resolver {
+    resolution: Resolution,
+    scopes: Vec<Scope>,
+    errors: SemanticErrors,
+}
+
+#[derive(Default)]
+struct Scope {
+    names: HashMap<Symbol, DeclId>,
+    /// Declarations later in the scope, for reporting uses before them.
+    pending: HashMap<Symbol, Span>,
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver {
+            resolution: Resolution::default(),
+            scopes: Vec::new(),
+            errors: SemanticErrors::default(),
+        }
+    }
+
+    pub fn resolve_program(mut self, program: &ast::Program) -> (Resolution, SemanticErrors) {
+        self.resolve_statements(&program.statements, None);
+        (self.resolution, self.errors)
+    }
+
+    /// Resolves the statements and optional tail of a program or block in a
+    /// new scope.
+    fn resolve_statements(&mut self, statements: &[ast::Statement], tail: Option<&ast::Expression>) {
+        let mut scope = Scope::default();
+        for statement in statements {
+            if let ast::Statement::Let(stmt) = statement {
+                scope.pending.entry(stmt.name.value).or_insert(stmt.name.span);
+            }
+        }
+        self.scopes.push(scope);
+
+        for statement in statements {
+            self.resolve_statement(statement);
+        }
+        if let Some(tail) = tail {
+            self.resolve_expression(tail);
+        }
+
+        self.scopes.pop();
+    }
+
+    fn resolve_statement(&mut self, statement: &ast::Statement) {
+        match statement {
+            ast::Statement::Let(stmt) => {
+                let kind = match stmt.modifier {
+                    Token::Var => DeclKind::Var,
+                    _ => DeclKind::Const,
+                };
+
+                //  a function can call itself recursively through its binding
+                if let ast::Expression::Function(_) = stmt.value {
+                    self.declare(&stmt.name, kind);
+                    self.resolve_expression(&stmt.value);
+                } else {
+                    self.resolve_expression(&stmt.value);
+                    self.declare(&stmt.name, kind);
+                }
+            },
+            ast::Statement::Return(stmt) => self.resolve_expression(&stmt.return_value),
+            ast::Statement::Expression(expr) => self.resolve_expression(expr),
+            ast::Statement::While(stmt) => {
+                self.resolve_expression(&stmt.condition);
+                self.resolve_block(&stmt.body);
+            },
+            ast::Statement::For(stmt) => {
+                self.resolve_expression(&stmt.iterable);
+                self.scopes.push(Scope::default());
+                self.declare(&stmt.variable, DeclKind::LoopVariable);
+                self.resolve_block(&stmt.body);
+                self.scopes.pop();
+            },
+            ast::Statement::Break(_) | ast::Statement::Continue(_) => {},
+            ast::Statement::Assign(stmt) => {
+                self.resolve_expression(&stmt.target);
+                self.resolve_expression(&stmt.value);
+            },
+        }
+    }
+
+    fn resolve_block(&mut self, block: &ast::BlockStatement) {
+        self.resolve_statements(&block.statements, block.tail.as_deref());
+    }
+
+    fn resolve_expression(&mut self, expression: &ast::Expression) {
+        match expression {
+            ast::Expression::Identifier(ident) => self.resolve_use(ident),
+            ast::Expression::Integer(_)
+            | ast::Expression::Float(_)
+            | ast::Expression::Boolean(_)
+            | ast::Expression::Str(_)
+            | ast::Expression::Null(_) => {},
+            ast::Expression::Prefix(expr) => self.resolve_expression(&expr.right),
+            ast::Expression::Infix(expr) => {
+                self.resolve_expression(&expr.left);
+                self.resolve_expression(&expr.right);
+            },
+            ast::Expression::Logical(expr) => {
+                self.resolve_expression(&expr.left);
+                self.resolve_expression(&expr.right);
+            },
+            ast::Expression::Range(expr) => {
+                self.resolve_expression(&expr.start);
+                self.resolve_expression(&expr.end);
+            },
+            ast::Expression::Function(expr) => {
+                self.scopes.push(Scope::default());
+                for parameter in &expr.parameters {
+                    self.declare(&parameter.name, DeclKind::Parameter);
+                }
+                self.resolve_block(&expr.body);
+                self.scopes.pop();
+            },
+            ast::Expression::Call(expr) => {
+                self.resolve_expression(&expr.function);
+                for argument in &expr.arguments {
+                    self.resolve_expression(argument);
+                }
+            },
+            ast::Expression::Index(expr) => {
+                self.resolve_expression(&expr.object);
+                self.resolve_expression(&expr.index);
+            },
+            //  fields belong to the object's type, not to any scope
+            ast::Expression::Field(expr) => self.resolve_expression(&expr.object),
+            ast::Expression::If(expr) => {
+                self.resolve_expression(&expr.condition);
+                self.resolve_block(&expr.consequence);
+                if let Some(alternative) = &expr.alternative {
+                    self.resolve_expression(alternative);
+                }
+            },
+            ast::Expression::Block(expr) => self.resolve_block(expr),
+        }
+    }
+
+    fn declare(&mut self, name: &ast::Identifier, kind: DeclKind) {
+        let id = DeclId(self.resolution.declarations.len());
+        self.resolution.declarations.push(Declaration {
+            name: name.value,
+            kind,
+            span: name.span,
+        });
+        self.resolution.bindings.insert(name.span, id);
+
+        let scope = self.scopes.last_mut().expect("is inside a scope");
+        scope.pending.remove(&name.value);
+        if let Some(previous) = scope.names.insert(name.value, id) {
+            let previous = self.resolution.declaration(previous).span;
+            self.errors.push_err(SemanticError::Duplicate { name: name.value, span: name.span, previous });
+        }
+    }
+
+    fn resolve_use(&mut self, ident: &ast::Identifier) {
+        for scope in self.scopes.iter().rev() {
+            if let Some(id) = scope.names.get(&ident.value) {
+                self.resolution.bindings.insert(ident.span, *id);
+                return;
+            }
+        }
+
+        let pending = self.scopes.iter().rev().find_map(|scope| scope.pending.get(&ident.value));
+        self.errors.push_err(match pending {
+            Some(declaration) => SemanticError::UseBeforeDeclaration { name: ident.value, span: ident.span, declaration: *declaration },
+            None => SemanticError::Undefined { name: ident.value, span: ident.span },
+        });
+    }
+}
+
+#[cfg(test)]
+fn resolve(input: &str) -> (ast::Program, Resolution, SemanticErrors) {
+    use crate::lexer::Lexer;
+    use crate::parser::parser::Parser;
+
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+    assert!(parser.errors().errors.is_empty(), "{}", parser.errors());
+
+    let (resolution, errors) = Resolver::new().resolve_program(&program);
+    (program, resolution, errors)
+}
+
+#[test]
+fn resolve_test() {
+    let input = r#"
+        const x: i32 = 0;
+        const add = fn(x: i32, y: i32) -> i32 {
+            return x + y;
+        };
+        const sum = add(x, 1);
+        const fact = fn(n: i32) -> i32 { if n < 2 { 1 } else { n * fact(n - 1) } };
+        for i in 0..sum {
+            var x = i;
+            x += i;
+        }
+        "#;
+    let (program, resolution, errors) = resolve(input);
+    assert!(errors.errors.is_empty(), "{errors}");
+
+    let ast::Statement::Let(sum) = &program.statements[2] else { panic!() };
+    let ast::Expression::Call(call) = &sum.value else { panic!() };
+    let ast::Expression::Identifier(add) = call.function.as_ref() else { panic!() };
+    let ast::Expression::Identifier(x) = &call.arguments[0] else { panic!() };
+
+    let add = resolution.declaration(resolution.lookup(add).unwrap());
+    assert_eq!((add.name.as_str(), add.kind, add.span.line), ("add", DeclKind::Const, 3));
+    let x = resolution.declaration(resolution.lookup(x).unwrap());
+    assert_eq!((x.name.as_str(), x.kind, x.span.line), ("x", DeclKind::Const, 2));
+
+    let kinds: Vec<(&str, DeclKind)> = resolution.declarations.iter().map(|decl| (decl.name.as_str(), decl.kind)).collect();
+    assert_eq!(kinds, vec![
+        ("x", DeclKind::Const),
+        ("add", DeclKind::Const),
+        ("x", DeclKind::Parameter),
+        ("y", DeclKind::Parameter),
+        ("sum", DeclKind::Const),
+        ("fact", DeclKind::Const),
+        ("n", DeclKind::Parameter),
+        ("i", DeclKind::LoopVariable),
+        ("x", DeclKind::Var),
+    ]);
+}
+
+#[test]
+fn resolve_errors_test() {
+    let input = r#"
+        const a = b;
+        const b = 1;
+        const c = missing + 1;
+        const b = 2;
+        const f = fn(p: i32, p: i32) { q };
+        {
+            const inner = 1;
+        }
+        const d = inner;
+        const e = e;
+        "#;
+    let (_, _, errors) = resolve(input);
+
+    let errors: Vec<String> = errors.errors.iter().map(|err| format!("{}: {err}", err.span())).collect();
+    assert_eq!(errors, vec![
+        "2:19: `b` is used before its declaration",
+        "4:19: Cannot find `missing` in this scope",
+        "5:15: `b` is declared more than once in this scope",
+        "6:30: `p` is declared more than once in this scope",
+        "6:40: Cannot find `q` in this scope",
+        "10:19: Cannot find `inner` in this scope",
+        "11:19: `e` is used before its declaration",
+    ]);
+}
@@ -0,0 +1,166 @@
+use std::error::Error;
+use std::fmt::{ Display, Formatter };
+
+use crate::diagnostics::{ Diagnostic, Label, ToDiagnostic };
+use crate::semantic::resolver::DeclKind;
+use crate::semantic::types::Type;
+use crate::source::{ Span, Symbol };
+
+#[derive(Debug, Default)]
+pub struct SemanticErrors {
+    pub errors: Vec<SemanticError>,
+}
+
+impl Error for SemanticErrors {
+}
+
+impl Display for SemanticErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Semantic errors:")?;
+        for err in &self.errors {
+            writeln!(f, "\t{}: {err}", err.span())?;
+        }
+        Ok(())
+    }
+}
+
+impl SemanticErrors {
+    pub fn new() -> SemanticErrors {
+        SemanticErrors {
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn push_err(&mut self, err: SemanticError) {
+        self.errors.push(err);
+    }
+
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors.iter().map(ToDiagnostic::to_diagnostic).collect()
+    }
+}
+
+/// An error found by the passes that run over a parsed program. Each variant
+/// carries the span it is reported at, along with any related spans.
+#[derive(Debug, PartialEq)]
+pub enum SemanticError {
+    Undefined { name: Symbol, span: Span },
+    Duplicate { name: Symbol, span: Span, previous: Span },
+    UseBeforeDeclaration { name: Symbol, span: Span, declaration: Span },
+    /// An assignment to a `const` binding or a parameter. The span covers the
+    /// whole assignment.
+    AssignToImmutable { name: Symbol, kind: DeclKind, span: Span, declaration: Span },
+    /// A value of the wrong type, with the span of what made the expected
+    /// type expected, such as an annotation, if there is one.
+    TypeMismatch { expected: Type, found: Type, span: Span, origin: Option<Span> },
+    /// A value where a kind of type was expected, such as "a function".
+    UnexpectedType { expected: &'static str, found: Type, span: Span },
+    InvalidOperands { operator: String, operands: Vec<Type>, span: Span },
+    ArityMismatch { expected: usize, found: usize, span: Span },
+    /// A function body that can reach its end without returning a value.
+    /// The span covers the body.
+    MissingReturn { expected: Type, span: Span, origin: Option<Span> },
+    NoField { field: Symbol, found: Type, span: Span },
+    /// An integer literal, possibly negated, that doesn't fit its type.
+    LiteralOutOfRange { literal: String, ty: Type, span: Span },
+}
+
+impl Display for SemanticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticError::Undefined { name, .. } => write!(f, "Cannot find `{}` in this scope", name),
+            SemanticError::Duplicate { name, .. } => write!(f, "`{}` is declared more than once in this scope", name),
+            SemanticError::UseBeforeDeclaration { name, .. } => write!(f, "`{}` is used before its declaration", name),
+            SemanticError::AssignToImmutable { name, kind: DeclKind::Parameter, .. } => write!(f, "Cannot assign to the parameter `{}`", name),
+            SemanticError::AssignToImmutable { name, .. } => write!(f, "Cannot assign to `{}`, which is declared `const`", name),
+            SemanticError::TypeMismatch { expected, found, .. } => write!(f, "Mismatched types: expected `{}`, found `{}`", expected, found),
+            SemanticError::UnexpectedType { expected, found, .. } => write!(f, "Expected {}, found `{}`", expected, found),
+            SemanticError::InvalidOperands { operator, operands, .. } => match operands.as_slice() {
+                [left, right] => write!(f, "Cannot apply `{}` to `{}` and `{}`", operator, left, right),
+                operands => {
+                    let operands: Vec<String> = operands.iter().map(|operand| format!("`{operand}`")).collect();
+                    write!(f, "Cannot apply `{}` to {}", operator, operands.join(", "))
+                },
+            },
+            SemanticError::ArityMismatch { expected, found, .. } => write!(f, "Expected {} argument(s), found {}", expected, found),
+            SemanticError::NoField { field, found, .. } => write!(f, "No field `{}` on type `{}`", field, found),
+            SemanticError::LiteralOutOfRange { literal, ty, .. } => write!(f, "Literal `{}` is out of range for `{}`", literal, ty),
+            SemanticError::MissingReturn { expected, .. } => write!(f, "Function can finish without returning a value of type `{}`", expected),
+        }
+    }
+}
+
+impl ToDiagnostic for SemanticError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.to_string());
+        return match self {
+            SemanticError::Undefined { span, .. } => diagnostic
+                .with_label(Label::primary(*span, "not found in this scope")),
+            SemanticError::Duplicate { name, span, previous } => diagnostic
+                .with_label(Label::primary(*span, format!("`{}` redeclared here", name)))
+                .with_label(Label::secondary(*previous, "first declared here"))
+                .with_help("use a different name, or declare it in an inner block to shadow it"),
+            SemanticError::UseBeforeDeclaration { span, declaration, .. } => diagnostic
+                .with_label(Label::primary(*span, "used here"))
+                .with_label(Label::secondary(*declaration, "declared here"))
+                .with_help("move the declaration before its first use"),
+            SemanticError::AssignToImmutable { name, kind: DeclKind::Parameter, span, declaration } => diagnostic
+                .with_label(Label::primary(*span, "cannot assign to a parameter"))
+                .with_label(Label::secondary(*declaration, format!("`{}` is a parameter", name)))
+                .with_help(format!("copy it into a binding first: `var {} = {};`", name, name)),
+            SemanticError::AssignToImmutable { name, span, declaration, .. } => diagnostic
+                .with_label(Label::primary(*span, "cannot assign to a constant"))
+                .with_label(Label::secondary(*declaration, format!("`{}` declared here", name)))
+                .with_help("declare it with `var` to make it mutable"),
+            SemanticError::TypeMismatch { expected, found, span, origin } => {
+                let diagnostic = diagnostic.with_label(Label::primary(*span, format!("expected `{}`, found `{}`", expected, found)));
+                match origin {
+                    Some(origin) => diagnostic.with_label(Label::secondary(*origin, "expected due to this")),
+                    None => diagnostic,
+                }
+            },
+            SemanticError::UnexpectedType { expected, span, .. } => diagnostic
+                .with_label(Label::primary(*span, format!("expected {}", expected))),
+            SemanticError::InvalidOperands { span, .. } => diagnostic
+                .with_label(Label::primary(*span, "invalid operand types")),
+            SemanticError::ArityMismatch { expected, span, .. } => diagnostic
+                .with_label(Label::primary(*span, format!("the function takes {} argument(s)", expected))),
+            SemanticError::MissingReturn { expected, span, origin } => {
+                let diagnostic = diagnostic
+                    .with_label(Label::primary(*span, "this body can reach its end without a value"))
+                    .with_help(format!("end the body with a value of type `{}`, or `return` one", expected));
+                match origin {
+                    Some(origin) => diagnostic.with_label(Label::secondary(*origin, "expected due to this")),
+                    None => diagnostic,
+                }
+            },
+            SemanticError::LiteralOutOfRange { ty, span, .. } => {
+                let (min, max) = ty.integer_range().expect("is an integer type");
+                diagnostic
+                    .with_label(Label::primary(*span, format!("doesn't fit in `{}`", ty)))
+                    .with_note(format!("`{}` holds values from {} to {}", ty, min, max))
+            },
+            SemanticError::NoField { span, .. } => diagnostic
+                .with_label(Label::primary(*span, "unknown field"))
+                .with_note("none of the built in types have fields"),
+        };
+    }
+}
+
+impl SemanticError {
+    pub fn span(&self) -> Span {
+        return match self {
+            SemanticError::Undefined { span, .. }
+            | SemanticError::Duplicate { span, .. }
+            | SemanticError::UseBeforeDeclaration { span, .. }
+            | SemanticError::AssignToImmutable { span, .. }
+            | SemanticError::TypeMismatch { span, .. }
+            | SemanticError::UnexpectedType { span, .. }
+            | SemanticError::InvalidOperands { span, .. }
+            | SemanticError::ArityMismatch { span, .. }
+            | SemanticError::MissingReturn { span, .. }
+            | SemanticError::NoField { span, .. }
+            | SemanticError::LiteralOutOfRange { span, .. } => *span,
+        };
+    }
+}
@@ -0,0 +1,646 @@
+use std::collections::HashMap;
+
+use crate::lexer::NumSuffix;
+use crate::parser::ast;
+use crate::semantic::resolver::{ DeclId, Resolution };
+use crate::semantic::semantic_errors::{ SemanticErrors, SemanticError };
+use crate::semantic::types::Type;
+use crate::source::Span;
+
+/// The type of every binding in a program, as found by the type checker.
+#[derive(Debug, Default)]
+pub struct TypeTable {
+    bindings: HashMap<DeclId, Type>,
+}
+
+impl TypeTable {
+    /// Returns the declared or inferred type of a binding, or `None` if the
+    /// checker never reached its declaration.
+    pub fn binding_type(&self, id: DeclId) -> Option<&Type> {
+        self.bindings.get(&id)
+    }
+}
+
+/// Computes a type for every expression of a resolved program and reports
+/// where they don't fit together.
+///
+/// A binding without an annotation takes the type of its initialiser, with
+/// unsuffixed literals defaulted, so `const x = 7;` is an `i32`. Inference is
+/// local: later uses of a binding never change its type. An expression that
+/// is already wrong gets `Type::Error`, which fits anywhere, so each mistake
+/// is reported once rather than at every use.
+///
+/// A function with a `->` annotation must return a value of that type on
+/// every path. Without one, the return type is inferred from its `return`
+/// values and tail, defaulted like a binding, and is `()` if it has neither.
+pub struct TypeChecker<'r> {
+    resolution: &'r Resolution,
+    types: TypeTable,
+    /// The return type of each enclosing function, innermost last.
+    returns: Vec<ReturnType>,
+    errors: SemanticErrors,
+}
+
+enum ReturnType {
+    /// Given by a `->` annotation, with its span.
+    Declared(Type, Span),
+    /// Inferred from the values returned so far, `None` before the first.
+    Inferred(Option<Type>),
+}
+
+impl<'r> TypeChecker<'r> {
+    pub fn new(resolution: &'r Resolution) -> TypeChecker<'r> {
+        TypeChecker {
+            resolution,
+            types: TypeTable::default(),
+            returns: Vec::new(),
+            errors: SemanticErrors::new(),
+        }
+    }
+
+    pub fn check_program(mut self, program: &ast::Program) -> (TypeTable, SemanticErrors) {
+        for statement in &program.statements {
+            self.check_statement(statement);
+        }
+        (self.types, self.errors)
+    }
+
+    /// Checks a statement, returning `Type::Never` if it never finishes and
+    /// `()` otherwise.
+    fn check_statement(&mut self, statement: &ast::Statement) -> Type {
+        match statement {
+            ast::Statement::Let(stmt) => self.check_let(stmt),
+            ast::Statement::Return(stmt) => {
+                let found = self.check_expression(&stmt.return_value);
+                self.check_return(found, &stmt.return_value);
+                return Type::Never;
+            },
+            ast::Statement::Expression(expr) => {
+                if self.check_expression(expr) == Type::Never {
+                    return Type::Never;
+                }
+            },
+            ast::Statement::While(stmt) => {
+                let condition = self.check_expression(&stmt.condition);
+                self.expect(&Type::Bool, &condition, &stmt.condition, None);
+                self.check_block(&stmt.body);
+            },
+            ast::Statement::For(stmt) => {
+                let iterable = self.check_expression(&stmt.iterable);
+                let element = match iterable {
+                    Type::Range(element) | Type::Array(element, _) => *element,
+                    Type::Error => Type::Error,
+                    found => {
+                        self.errors.push_err(SemanticError::UnexpectedType { expected: "a range or an array", found, span: stmt.iterable.span() });
+                        Type::Error
+                    },
+                };
+                self.bind(&stmt.variable, element.defaulted());
+                self.check_block(&stmt.body);
+            },
+            ast::Statement::Break(_) | ast::Statement::Continue(_) => return Type::Never,
+            ast::Statement::Assign(stmt) => {
+                let target = self.check_expression(&stmt.target);
+                let value = self.check_expression(&stmt.value);
+                let value = match stmt.operator {
+                    Some(operator) => self.check_infix(operator, target.clone(), value, stmt.span),
+                    None => value,
+                };
+                self.expect(&target, &value, &stmt.value, Some(stmt.target.span()));
+            },
+        }
+
+        Type::Unit
+    }
+
+    fn check_let(&mut self, stmt: &ast::LetStatement) {
+        let annotation = stmt.type_annotation.as_ref().map(Type::from_type_expr);
+
+        //  bound before the body is checked, so a function can call itself
+        if let ast::Expression::Function(function) = &stmt.value {
+            let signature = annotation.clone().unwrap_or_else(|| signature(function));
+            self.bind(&stmt.name, signature);
+        }
+
+        let found = self.check_expression(&stmt.value);
+        let binding = match (annotation, &stmt.type_annotation) {
+            (Some(expected), Some(type_expr)) => {
+                self.expect(&expected, &found, &stmt.value, Some(type_expr.span()));
+                expected
+            },
+            _ => {
+                //  an unsuffixed literal becomes an `i32` here, so it has to fit one
+                if found == Type::IntegerLiteral {
+                    self.check_literal_range(&Type::I32, &stmt.value);
+                }
+                found.defaulted()
+            },
+        };
+        self.bind(&stmt.name, binding);
+    }
+
+    /// Returns the type of a block: `Type::Never` if one of its statements
+    /// never finishes, otherwise the type of its tail or `()` without one.
+    fn check_block(&mut self, block: &ast::BlockStatement) -> Type {
+        let mut diverges = false;
+        for statement in &block.statements {
+            diverges |= self.check_statement(statement) == Type::Never;
+        }
+        let tail = match &block.tail {
+            Some(tail) => self.check_expression(tail),
+            None => Type::Unit,
+        };
+
+        return if diverges { Type::Never } else { tail };
+    }
+
+    fn check_expression(&mut self, expression: &ast::Expression) -> Type {
+        return match expression {
+            ast::Expression::Identifier(ident) => self.resolution.lookup(ident)
+                .and_then(|id| self.types.binding_type(id).cloned())
+                .unwrap_or(Type::Error),
+            ast::Expression::Integer(lit) => {
+                let ty = lit.suffix.map(Type::from_suffix).unwrap_or(Type::IntegerLiteral);
+                self.check_literal_range(&ty, expression);
+                ty
+            },
+            ast::Expression::Float(lit) => lit.suffix.map(Type::from_suffix).unwrap_or(Type::FloatLiteral),
+            ast::Expression::Boolean(_) => Type::Bool,
+            ast::Expression::Str(_) => Type::String,
+            ast::Expression::Null(_) => Type::Null,
+            ast::Expression::Prefix(expr) => {
+                //  a negated literal is range checked as a whole, so `-128i8`
+                //  isn't rejected for the `128i8` in it
+                let operand = match (&expr.operator, expr.right.as_ref()) {
+                    (ast::PrefixOperator::Negate, ast::Expression::Integer(lit)) => lit.suffix.map(Type::from_suffix).unwrap_or(Type::IntegerLiteral),
+                    _ => self.check_expression(&expr.right),
+                };
+                let valid = match expr.operator {
+                    ast::PrefixOperator::Not => Type::Bool.accepts(&operand),
+                    ast::PrefixOperator::Negate => operand.is_numeric() && operand != Type::U8,
+                };
+                if valid {
+                    self.check_literal_range(&operand, expression);
+                    operand
+                } else {
+                    self.errors.push_err(SemanticError::InvalidOperands { operator: expr.operator.to_string(), operands: vec![operand], span: expr.span });
+                    Type::Error
+                }
+            },
+            ast::Expression::Infix(expr) => {
+                let left = self.check_expression(&expr.left);
+                let right = self.check_expression(&expr.right);
+                if let Some(operands) = left.unify(&right) {
+                    self.check_literal_range(&operands, &expr.left);
+                    self.check_literal_range(&operands, &expr.right);
+                }
+                self.check_infix(expr.operator, left, right, expr.span)
+            },
+            ast::Expression::Logical(expr) => {
+                let left = self.check_expression(&expr.left);
+                self.expect(&Type::Bool, &left, &expr.left, None);
+                let right = self.check_expression(&expr.right);
+                self.expect(&Type::Bool, &right, &expr.right, None);
+                Type::Bool
+            },
+            ast::Expression::Range(expr) => {
+                let start = self.check_expression(&expr.start);
+                let end = self.check_expression(&expr.end);
+                match start.unify(&end) {
+                    Some(element) if element.is_integer() => Type::Range(Box::new(element)),
+                    _ if start == Type::Error || end == Type::Error => Type::Range(Box::new(Type::Error)),
+                    _ => {
+                        let operator = if expr.inclusive { "..=" } else { ".." };
+                        self.errors.push_err(SemanticError::InvalidOperands { operator: operator.to_string(), operands: vec![start, end], span: expr.span });
+                        Type::Range(Box::new(Type::Error))
+                    },
+                }
+            },
+            ast::Expression::Function(expr) => self.check_function(expr),
+            ast::Expression::Call(expr) => {
+                let function = self.check_expression(&expr.function);
+                let arguments: Vec<Type> = expr.arguments.iter().map(|argument| self.check_expression(argument)).collect();
+
+                match function {
+                    Type::Function(parameters, return_type) => {
+                        if parameters.len() != arguments.len() {
+                            self.errors.push_err(SemanticError::ArityMismatch { expected: parameters.len(), found: arguments.len(), span: expr.span });
+                        }
+                        for ((parameter, argument), expression) in parameters.iter().zip(&arguments).zip(&expr.arguments) {
+                            self.expect(parameter, argument, expression, None);
+                        }
+                        *return_type
+                    },
+                    Type::Error => Type::Error,
+                    found => {
+                        self.errors.push_err(SemanticError::UnexpectedType { expected: "a function", found, span: expr.function.span() });
+                        Type::Error
+                    },
+                }
+            },
+            ast::Expression::Index(expr) => {
+                let object = self.check_expression(&expr.object);
+                let index = self.check_expression(&expr.index);
+                if !index.is_integer() {
+                    self.errors.push_err(SemanticError::UnexpectedType { expected: "an integer index", found: index, span: expr.index.span() });
+                }
+
+                match object {
+                    Type::Array(element, _) => *element,
+                    Type::Error => Type::Error,
+                    found => {
+                        self.errors.push_err(SemanticError::UnexpectedType { expected: "an array", found, span: expr.object.span() });
+                        Type::Error
+                    },
+                }
+            },
+            ast::Expression::Field(expr) => {
+                let object = self.check_expression(&expr.object);
+                if object != Type::Error {
+                    self.errors.push_err(SemanticError::NoField { field: expr.field.value, found: object, span: expr.field.span });
+                }
+                Type::Error
+            },
+            ast::Expression::If(expr) => {
+                let condition = self.check_expression(&expr.condition);
+                self.expect(&Type::Bool, &condition, &expr.condition, None);
+                let consequence = self.check_block(&expr.consequence);
+
+                match &expr.alternative {
+                    Some(alternative) => {
+                        let found = self.check_expression(alternative);
+                        consequence.unify(&found).unwrap_or_else(|| {
+                            let origin = expr.consequence.tail.as_ref().map(|tail| tail.span()).unwrap_or(expr.consequence.span);
+                            self.errors.push_err(SemanticError::TypeMismatch { expected: consequence, found, span: alternative.span(), origin: Some(origin) });
+                            Type::Error
+                        })
+                    },
+                    None => Type::Unit,
+                }
+            },
+            ast::Expression::Block(expr) => self.check_block(expr),
+        };
+    }
+
+    fn check_function(&mut self, function: &ast::FunctionLiteral) -> Type {
+        for parameter in &function.parameters {
+            self.bind(&parameter.name, Type::from_type_expr(&parameter.param_type));
+        }
+
+        self.returns.push(match &function.return_type {
+            Some(type_expr) => ReturnType::Declared(Type::from_type_expr(type_expr), type_expr.span()),
+            None => ReturnType::Inferred(None),
+        });
+        let found = self.check_block(&function.body);
+        let returns = self.returns.pop().expect("was pushed above");
+
+        let return_type = match returns {
+            ReturnType::Declared(expected, origin) => {
+                self.check_body(&expected, found, &function.body, Some(origin));
+                expected
+            },
+            ReturnType::Inferred(None) if found == Type::Never => Type::Unit,
+            ReturnType::Inferred(None) => found.defaulted(),
+            ReturnType::Inferred(Some(returned)) => {
+                self.check_body(&returned, found.clone(), &function.body, None);
+                returned.unify(&found).unwrap_or(returned).defaulted()
+            },
+        };
+
+        let Type::Function(parameters, _) = signature(function) else { unreachable!() };
+        Type::Function(parameters, Box::new(return_type))
+    }
+
+    /// Checks what a function body finishes with against its return type.
+    fn check_body(&mut self, expected: &Type, found: Type, body: &ast::BlockStatement, origin: Option<Span>) {
+        //  an `if` without `else` at the end parses as the tail, but like a
+        //  missing tail it leaves the function without a value
+        let falls_through = body.tail.as_ref().is_none_or(|tail| tail.is_block_like());
+        if falls_through && found == Type::Unit && *expected != Type::Unit && *expected != Type::Error {
+            self.errors.push_err(SemanticError::MissingReturn { expected: expected.clone(), span: body.span, origin });
+        } else if let Some(tail) = &body.tail {
+            self.expect(expected, &found, tail, origin);
+        }
+    }
+
+    fn check_return(&mut self, found: Type, value: &ast::Expression) {
+        match self.returns.last_mut() {
+            Some(ReturnType::Declared(expected, origin)) => {
+                let (expected, origin) = (expected.clone(), *origin);
+                self.expect(&expected, &found, value, Some(origin));
+            },
+            Some(ReturnType::Inferred(returned @ None)) => *returned = Some(found),
+            Some(ReturnType::Inferred(Some(returned))) => match returned.unify(&found) {
+                Some(unified) => *returned = unified,
+                None => {
+                    let expected = returned.clone();
+                    self.errors.push_err(SemanticError::TypeMismatch { expected, found, span: value.span(), origin: None });
+                },
+            },
+            //  a `return` outside of any function has nothing to check against
+            None => {},
+        }
+    }
+
+    /// Returns the type of `left operator right`, reporting operands the
+    /// operator doesn't apply to.
+    fn check_infix(&mut self, operator: ast::InfixOperator, left: Type, right: Type, span: Span) -> Type {
+        let unified = left.unify(&right);
+        let result = match operator {
+            ast::InfixOperator::Plus
+            | ast::InfixOperator::Minus
+            | ast::InfixOperator::Multiply
+            | ast::InfixOperator::Divide
+            | ast::InfixOperator::Modulo => unified.filter(Type::is_numeric),
+            ast::InfixOperator::Eq | ast::InfixOperator::NotEq => unified.map(|_| Type::Bool),
+            ast::InfixOperator::Lt
+            | ast::InfixOperator::Gt
+            | ast::InfixOperator::LtEq
+            | ast::InfixOperator::GtEq => unified.filter(Type::is_numeric).map(|_| Type::Bool),
+            ast::InfixOperator::BitAnd
+            | ast::InfixOperator::BitOr
+            | ast::InfixOperator::BitXor => unified.filter(Type::is_integer),
+            //  the shift amount needn't have the type of the value shifted
+            ast::InfixOperator::Shl | ast::InfixOperator::Shr => {
+                Some(left.clone()).filter(|_| left.is_integer() && right.is_integer())
+            },
+        };
+
+        //  an operand that's already wrong has been reported, but the other
+        //  side can still give the result a type
+        if result.is_none() && left != Type::Error && right != Type::Error {
+            self.errors.push_err(SemanticError::InvalidOperands { operator: operator.to_string(), operands: vec![left, right], span });
+        }
+        result.unwrap_or(Type::Error)
+    }
+
+    fn expect(&mut self, expected: &Type, found: &Type, expression: &ast::Expression, origin: Option<Span>) {
+        if !expected.accepts(found) {
+            self.errors.push_err(SemanticError::TypeMismatch { expected: expected.clone(), found: found.clone(), span: expression.span(), origin });
+        } else {
+            self.check_literal_range(expected, expression);
+        }
+    }
+
+    /// Reports an integer literal, or a negated one, whose value doesn't fit
+    /// the integer type it's used as.
+    fn check_literal_range(&mut self, expected: &Type, expression: &ast::Expression) {
+        let (negated, literal) = match expression {
+            ast::Expression::Integer(literal) => (false, literal),
+            ast::Expression::Prefix(expr) => match (&expr.operator, expr.right.as_ref()) {
+                (ast::PrefixOperator::Negate, ast::Expression::Integer(literal)) => (true, literal),
+                _ => return,
+            },
+            _ => return,
+        };
+        let ty = match expected {
+            Type::Optional(inner) => inner.as_ref(),
+            ty => ty,
+        };
+        let Some((min, max)) = ty.integer_range() else {
+            return;
+        };
+
+        let value = if negated { -i128::from(literal.value) } else { i128::from(literal.value) };
+        if value < min || value > max {
+            self.errors.push_err(SemanticError::LiteralOutOfRange {
+                literal: format!("{value}{}", NumSuffix::display(&literal.suffix)),
+                ty: ty.clone(),
+                span: expression.span(),
+            });
+        }
+    }
+
+    fn bind(&mut self, name: &ast::Identifier, ty: Type) {
+        if let Some(id) = self.resolution.lookup(name) {
+            self.types.bindings.insert(id, ty);
+        }
+    }
+}
+
+/// The type of a function literal, from its annotations. A return type
+/// that is still to be inferred is `Type::Error`, so recursive calls made
+/// before it's known aren't reported.
+fn signature(function: &ast::FunctionLiteral) -> Type {
+    let parameters = function.parameters.iter().map(|parameter| Type::from_type_expr(&parameter.param_type)).collect();
+    let return_type = function.return_type.as_ref().map(Type::from_type_expr).unwrap_or(Type::Error);
+    Type::Function(parameters, Box::new(return_type))
+}
+
+#[cfg(test)]
+fn check(input: &str) -> Vec<String> {
+    use crate::lexer::Lexer;
+    use crate::parser::parser::Parser;
+    use crate::semantic::resolver::Resolver;
+
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+    assert!(parser.errors().errors.is_empty(), "{}", parser.errors());
+
+    let (resolution, errors) = Resolver::new().resolve_program(&program);
+    assert!(errors.errors.is_empty(), "{errors}");
+    let (_, errors) = TypeChecker::new(&resolution).check_program(&program);
+    errors.errors.iter().map(|err| format!("{}: {err}", err.span())).collect()
+}
+
+#[test]
+fn type_check_test() {
+    let input = r#"
+        const x: i32 = 0;
+        const y: i32 = 0;
+        const add = fn(x: i32, y: i32) -> i32 {
+            return x + y;
+        };
+        const sum: i32 = add(x, y);
+        const z: f32 = 4.2;
+        const big = 1i64 << 3;
+        const maybe: ?i64 = null;
+        const fact = fn(n: i64) -> i64 { if n < 2 { 1 } else { n * fact(n - 1) } };
+        var total = 0;
+        for i in 0..10 {
+            total += i * 2;
+        }
+        const first = fn(bytes: [4]u8) -> bool { bytes[0] == 1u8 && !false };
+        const scaled = -z * 2.0;
+        "#;
+    assert_eq!(check(input), Vec::<String>::new());
+}
+
+#[test]
+fn diverging_branch_test() {
+    let input = r#"
+        const f = fn(c: bool) -> i32 { if c { return 1; } else { 2 } };
+        const g = fn(c: bool) -> i32 { if c { 1 } else { return 2; } };
+        const h = fn(n: i32) -> i32 {
+            var total = 0;
+            for i in 0..n {
+                const step = if i == 3 { continue; } else { i };
+                total += if step > 5 { break; } else { step };
+            }
+            total
+        };
+        "#;
+    assert_eq!(check(input), Vec::<String>::new());
+
+    let input = "const f = fn(c: bool) -> i32 { if c { return 1; } else { true } };";
+    assert_eq!(check(input), vec!["1:32: Mismatched types: expected `i32`, found `bool`"]);
+}
+
+#[test]
+fn function_return_test() {
+    let input = r#"
+        const none = fn() -> i32 { };
+        const some_paths = fn(c: bool) -> i32 {
+            if c { return 1; }
+        };
+        const unit = fn() { };
+        const mixed = fn(c: bool) {
+            if c { return 1; }
+            "two"
+        };
+        const partial = fn(c: bool) {
+            if c { return 1.5; }
+        };
+        "#;
+    assert_eq!(check(input), vec![
+        "2:34: Function can finish without returning a value of type `i32`",
+        "3:47: Function can finish without returning a value of type `i32`",
+        "9:13: Mismatched types: expected `{integer}`, found `String`",
+        "11:37: Function can finish without returning a value of type `{float}`",
+    ]);
+
+    let input = r#"
+        const one = fn() { return 1; };
+        const half = fn(c: bool) { if c { return 0.5; } 1.5f32 };
+        const fact = fn(n: i64) { if n < 2 { return 1; } n * fact(n - 1) };
+        const nothing = fn() { };
+        const x: i32 = one();
+        const y: f32 = half(true);
+        const z: i64 = fact(5);
+        const w = nothing();
+        "#;
+    assert_eq!(check(input), Vec::<String>::new());
+}
+
+#[test]
+fn type_errors_test() {
+    let input = r#"
+        const a: i32 = true;
+        const b: f32 = 1;
+        const c = 1 + 2.0;
+        const add = fn(x: i32, y: i32) -> i32 {
+            return x == y;
+        };
+        const d = add(1);
+        const e: i64 = add(1, 2);
+        const f = a(1);
+        const g = fn() -> bool { 1 };
+        const h = if a { 1 } else { "one" };
+        for i in true {
+            -"no";
+        }
+        const j = !1 || 2.5;
+        const k = d.len;
+        "#;
+    assert_eq!(check(input), vec![
+        "2:24: Mismatched types: expected `i32`, found `bool`",
+        "3:24: Mismatched types: expected `f32`, found `{integer}`",
+        "4:19: Cannot apply `+` to `{integer}` and `{float}`",
+        "6:20: Mismatched types: expected `i32`, found `bool`",
+        "8:19: Expected 2 argument(s), found 1",
+        "9:24: Mismatched types: expected `i64`, found `i32`",
+        "10:19: Expected a function, found `i32`",
+        "11:34: Mismatched types: expected `bool`, found `{integer}`",
+        "12:22: Mismatched types: expected `bool`, found `i32`",
+        "12:35: Mismatched types: expected `{integer}`, found `String`",
+        "13:18: Expected a range or an array, found `bool`",
+        "14:13: Cannot apply `-` to `String`",
+        "16:19: Cannot apply `!` to `{integer}`",
+        "16:25: Mismatched types: expected `bool`, found `{float}`",
+        "17:21: No field `len` on type `i32`",
+    ]);
+}
+
+#[test]
+fn inference_test() {
+    use crate::lexer::Lexer;
+    use crate::parser::parser::Parser;
+    use crate::semantic::resolver::Resolver;
+
+    let input = r#"
+        const x = 7;
+        const y = 2.5;
+        const z = 7i64 * 3;
+        var w: ?u8 = 1;
+        const range = 0..=x;
+        const add = fn(a: i32, b: i32) -> i32 { a + b };
+        const sum = add(x, 1);
+        for i in 0..10 {
+            const half = y / 2.0;
+        }
+        "#;
+    let mut parser = Parser::new(Lexer::new(input));
+    let program = parser.parse_program();
+    let (resolution, _) = Resolver::new().resolve_program(&program);
+    let (types, errors) = TypeChecker::new(&resolution).check_program(&program);
+    assert!(errors.errors.is_empty(), "{errors}");
+
+    let bindings: Vec<String> = resolution.declarations.iter().enumerate()
+        .map(|(id, decl)| format!("{}: {}", decl.name, types.binding_type(DeclId(id)).expect("is checked")))
+        .collect();
+    assert_eq!(bindings, vec![
+        "x: i32",
+        "y: f64",
+        "z: i64",
+        "w: ?u8",
+        "range: Range<i32>",
+        "add: fn(i32, i32) -> i32",
+        "a: i32",
+        "b: i32",
+        "sum: i32",
+        "i: i32",
+        "half: f64",
+    ]);
+
+    let mut parser = Parser::new(Lexer::new("const x = 7;\nconst y: i64 = x;\n"));
+    let program = parser.parse_program();
+    let (resolution, _) = Resolver::new().resolve_program(&program);
+    let (_, errors) = TypeChecker::new(&resolution).check_program(&program);
+    let errors: Vec<String> = errors.errors.iter().map(|err| format!("{}: {err}", err.span())).collect();
+    assert_eq!(errors, vec!["2:16: Mismatched types: expected `i64`, found `i32`"]);
+}
+
+#[test]
+fn literal_range_test() {
+    let input = r#"
+        const a: i8 = -128;
+        const b: u8 = 255;
+        const c = -128i8;
+        const d = 255u8;
+        const e: ?u8 = 0;
+        const f = 2147483647;
+        const g: i64 = 1 << 40;
+        "#;
+    assert_eq!(check(input), Vec::<String>::new());
+
+    let input = r#"
+        const x: u8 = -1;
+        const y: u8 = 300;
+        const z: i8 = 1000;
+        const w = 300u8;
+        const v = -129i8;
+        const u = 2147483648;
+        const t: ?i8 = 128;
+        const s = 1u8 + 256;
+        "#;
+    assert_eq!(check(input), vec![
+        "2:23: Literal `-1` is out of range for `u8`",
+        "3:23: Literal `300` is out of range for `u8`",
+        "4:23: Literal `1000` is out of range for `i8`",
+        "5:19: Literal `300u8` is out of range for `u8`",
+        "6:19: Literal `-129i8` is out of range for `i8`",
+        "7:19: Literal `2147483648` is out of range for `i32`",
+        "8:24: Literal `128` is out of range for `i8`",
+        "9:25: Literal `256` is out of range for `u8`",
+    ]);
+}
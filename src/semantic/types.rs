@@ -0,0 +1,217 @@
+use std::fmt::Display;
+
+use crate::lexer::{ Token, NumSuffix };
+use crate::parser::ast;
+
+/// The type of an expression or binding, as computed by the type checker.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Type {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    F8,
+    F16,
+    F32,
+    F64,
+    String,
+    /// The type of `null`, which only fits an optional.
+    Null,
+    /// What statements, and functions without a `->` annotation, produce.
+    Unit,
+    /// The type of a block that never finishes, as it always leaves through
+    /// `return`, `break` or `continue`. It fits any type.
+    Never,
+    Optional(Box<Type>),
+    Array(Box<Type>, Option<u64>),
+    Function(Vec<Type>, Box<Type>),
+    Range(Box<Type>),
+    /// An integer literal without a suffix, which fits any integer type.
+    IntegerLiteral,
+    /// A float literal without a suffix, which fits any float type.
+    FloatLiteral,
+    /// The type of an expression that has already been reported as invalid.
+    /// It fits everywhere, so one mistake isn't reported over and over.
+    Error,
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Bool => write!(f, "bool"),
+            Type::I8 => write!(f, "i8"),
+            Type::I16 => write!(f, "i16"),
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
+            Type::U8 => write!(f, "u8"),
+            Type::F8 => write!(f, "f8"),
+            Type::F16 => write!(f, "f16"),
+            Type::F32 => write!(f, "f32"),
+            Type::F64 => write!(f, "f64"),
+            Type::String => write!(f, "String"),
+            Type::Null => write!(f, "null"),
+            Type::Unit => write!(f, "()"),
+            Type::Never => write!(f, "!"),
+            Type::Optional(inner) => write!(f, "?{inner}"),
+            Type::Array(element, Some(length)) => write!(f, "[{length}]{element}"),
+            Type::Array(element, None) => write!(f, "[{element}]"),
+            Type::Function(parameters, return_type) => {
+                let parameters: Vec<String> = parameters.iter().map(|param| param.to_string()).collect();
+                write!(f, "fn({})", parameters.join(", "))?;
+                if **return_type != Type::Unit {
+                    write!(f, " -> {return_type}")?;
+                }
+                Ok(())
+            },
+            Type::Range(element) => write!(f, "Range<{element}>"),
+            Type::IntegerLiteral => write!(f, "{{integer}}"),
+            Type::FloatLiteral => write!(f, "{{float}}"),
+            Type::Error => write!(f, "{{error}}"),
+        }
+    }
+}
+
+impl Type {
+    pub fn from_type_expr(type_expr: &ast::TypeExpr) -> Type {
+        return match type_expr {
+            ast::TypeExpr::Primitive(ty) => match ty.token {
+                Token::Bool => Type::Bool,
+                Token::I8 => Type::I8,
+                Token::I16 => Type::I16,
+                Token::I32 => Type::I32,
+                Token::I64 => Type::I64,
+                Token::U8 => Type::U8,
+                Token::F8 => Type::F8,
+                Token::F16 => Type::F16,
+                Token::F32 => Type::F32,
+                Token::F64 => Type::F64,
+                Token::StringTok => Type::String,
+                _ => unreachable!("the parser only accepts primitive type keywords"),
+            },
+            ast::TypeExpr::Optional(ty) => Type::Optional(Box::new(Type::from_type_expr(&ty.inner))),
+            ast::TypeExpr::Array(ty) => Type::Array(Box::new(Type::from_type_expr(&ty.element)), ty.length),
+            ast::TypeExpr::Function(ty) => Type::Function(
+                ty.parameters.iter().map(Type::from_type_expr).collect(),
+                Box::new(ty.return_type.as_deref().map(Type::from_type_expr).unwrap_or(Type::Unit)),
+            ),
+        };
+    }
+
+    pub fn from_suffix(suffix: NumSuffix) -> Type {
+        return match suffix {
+            NumSuffix::I8 => Type::I8,
+            NumSuffix::I16 => Type::I16,
+            NumSuffix::I32 => Type::I32,
+            NumSuffix::I64 => Type::I64,
+            NumSuffix::U8 => Type::U8,
+            NumSuffix::F8 => Type::F8,
+            NumSuffix::F16 => Type::F16,
+            NumSuffix::F32 => Type::F32,
+            NumSuffix::F64 => Type::F64,
+        };
+    }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::U8 | Type::IntegerLiteral | Type::Error)
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::F8 | Type::F16 | Type::F32 | Type::F64 | Type::FloatLiteral | Type::Error)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    /// Returns the smallest and largest values of an integer type.
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        return match self {
+            Type::I8 => Some((i8::MIN.into(), i8::MAX.into())),
+            Type::I16 => Some((i16::MIN.into(), i16::MAX.into())),
+            Type::I32 => Some((i32::MIN.into(), i32::MAX.into())),
+            Type::I64 => Some((i64::MIN.into(), i64::MAX.into())),
+            Type::U8 => Some((u8::MIN.into(), u8::MAX.into())),
+            _ => None,
+        };
+    }
+
+    /// Gives unsuffixed literals their default type, `i32` for integers and
+    /// `f64` for floats, so a binding like `const x = 7;` has a concrete type.
+    pub fn defaulted(self) -> Type {
+        return match self {
+            Type::IntegerLiteral => Type::I32,
+            Type::FloatLiteral => Type::F64,
+            Type::Optional(inner) => Type::Optional(Box::new(inner.defaulted())),
+            Type::Array(element, length) => Type::Array(Box::new(element.defaulted()), length),
+            Type::Range(element) => Type::Range(Box::new(element.defaulted())),
+            ty => ty,
+        };
+    }
+
+    /// Whether a value of type `found` can be used where `self` is expected.
+    pub fn accepts(&self, found: &Type) -> bool {
+        return match (self, found) {
+            (Type::Error, _) | (_, Type::Error) | (_, Type::Never) => true,
+            (Type::Optional(_), Type::Null) => true,
+            (Type::Optional(expected), Type::Optional(found)) => expected.accepts(found),
+            (Type::Optional(expected), found) => expected.accepts(found),
+            (Type::IntegerLiteral, found) => found.is_integer(),
+            (expected, Type::IntegerLiteral) => expected.is_integer(),
+            (Type::FloatLiteral, found) => found.is_float(),
+            (expected, Type::FloatLiteral) => expected.is_float(),
+            (Type::Array(expected, None), Type::Array(found, _)) => **expected == **found,
+            (Type::Array(expected, Some(length)), Type::Array(found, Some(found_length))) => {
+                length == found_length && **expected == **found
+            },
+            (expected, found) => expected == found,
+        };
+    }
+
+    /// Returns the type both operands of an operator can be taken as, where
+    /// an unsuffixed literal takes the type of the other side.
+    pub fn unify(&self, other: &Type) -> Option<Type> {
+        return match (self, other) {
+            (Type::Error, other) | (other, Type::Error) => Some(other.clone()),
+            (Type::Never, other) | (other, Type::Never) => Some(other.clone()),
+            (Type::IntegerLiteral, other) | (other, Type::IntegerLiteral) if other.is_integer() => Some(other.clone()),
+            (Type::FloatLiteral, other) | (other, Type::FloatLiteral) if other.is_float() => Some(other.clone()),
+            (Type::Null, Type::Optional(_)) => Some(other.clone()),
+            (Type::Optional(_), Type::Null) => Some(self.clone()),
+            (left, right) if left == right => Some(left.clone()),
+            _ => None,
+        };
+    }
+}
+
+#[test]
+fn type_test() {
+    assert!(Type::I32.accepts(&Type::IntegerLiteral));
+    assert!(Type::U8.accepts(&Type::IntegerLiteral));
+    assert!(!Type::F32.accepts(&Type::IntegerLiteral));
+    assert!(Type::F32.accepts(&Type::FloatLiteral));
+    assert!(!Type::I32.accepts(&Type::I64));
+    assert!(Type::Optional(Box::new(Type::I64)).accepts(&Type::Null));
+    assert!(Type::Optional(Box::new(Type::I64)).accepts(&Type::IntegerLiteral));
+    assert!(!Type::I64.accepts(&Type::Null));
+    assert!(Type::Array(Box::new(Type::U8), None).accepts(&Type::Array(Box::new(Type::U8), Some(4))));
+    assert!(!Type::Array(Box::new(Type::U8), Some(2)).accepts(&Type::Array(Box::new(Type::U8), Some(4))));
+
+    assert_eq!(Type::IntegerLiteral.unify(&Type::I8), Some(Type::I8));
+    assert_eq!(Type::FloatLiteral.unify(&Type::FloatLiteral), Some(Type::FloatLiteral));
+    assert_eq!(Type::IntegerLiteral.unify(&Type::F32), None);
+    assert_eq!(Type::Bool.unify(&Type::Error), Some(Type::Bool));
+    assert_eq!(Type::Never.unify(&Type::IntegerLiteral), Some(Type::IntegerLiteral));
+    assert_eq!(Type::String.unify(&Type::Never), Some(Type::String));
+    assert!(Type::I64.accepts(&Type::Never));
+
+    assert_eq!(Type::IntegerLiteral.defaulted(), Type::I32);
+    assert_eq!(Type::Range(Box::new(Type::IntegerLiteral)).defaulted(), Type::Range(Box::new(Type::I32)));
+    assert_eq!(Type::Array(Box::new(Type::FloatLiteral), Some(2)).defaulted(), Type::Array(Box::new(Type::F64), Some(2)));
+    assert_eq!(Type::I8.defaulted(), Type::I8);
+
+    let function = Type::Function(vec![Type::I32, Type::Optional(Box::new(Type::String))], Box::new(Type::Array(Box::new(Type::U8), Some(4))));
+    assert_eq!(function.to_string(), "fn(i32, ?String) -> [4]u8");
+    assert_eq!(Type::Function(Vec::new(), Box::new(Type::Unit)).to_string(), "fn()");
+}
@@ -0,0 +1,7 @@
+pub mod span;
+pub mod source_map;
+pub mod symbol;
+
+pub use span::{ FileId, Span };
+pub use source_map::SourceMap;
+pub use symbol::{ Symbol, kw };
@@ -0,0 +1,98 @@
+use crate::source::FileId;
+
+pub struct SourceFile {
+    pub name: String,
+    pub src: String,
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    pub fn new(name: String, src: String) -> SourceFile {
+        let mut line_starts = vec![0];
+        for (i, byte) in src.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+
+        SourceFile {
+            name,
+            src,
+            line_starts,
+        }
+    }
+
+    /// Returns the 1-based line and column of a byte offset. Columns count
+    /// characters, so a multi-byte character only advances the column by one.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let col = self.src[self.line_starts[line]..offset].chars().count() + 1;
+
+        (line + 1, col)
+    }
+
+    /// Returns the byte offset at which a 1-based line starts.
+    pub fn line_start(&self, line: usize) -> usize {
+        self.line_starts[line - 1]
+    }
+
+    /// Returns the text of a 1-based line without its line terminator.
+    pub fn line_text(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = match self.line_starts.get(line) {
+            Some(next) => *next,
+            None => self.src.len(),
+        };
+
+        self.src[start..end].trim_end_matches(['\n', '\r'])
+    }
+}
+
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap {
+            files: Vec::new(),
+        }
+    }
+
+    pub fn add_file(&mut self, name: String, src: String) -> FileId {
+        let id = FileId(self.files.len());
+        self.files.push(SourceFile::new(name, src));
+        id
+    }
+
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0]
+    }
+}
+
+#[test]
+fn line_col_lookup_test() {
+    let mut map = SourceMap::new();
+    let id = map.add_file(String::from("test.ind"), String::from("const x = 0;\n\nvar y = x;\n"));
+    let file = map.get(id);
+
+    assert_eq!(file.line_col(0), (1, 1));
+    assert_eq!(file.line_col(6), (1, 7));
+    assert_eq!(file.line_col(12), (1, 13));
+    assert_eq!(file.line_col(13), (2, 1));
+    assert_eq!(file.line_col(18), (3, 5));
+    assert_eq!(file.line_text(3), "var y = x;");
+    assert_eq!(file.line_text(2), "");
+    assert_eq!(file.line_starts.len(), 4);
+
+    let id = map.add_file(String::from("café.ind"), String::from("const café = 1;\nx"));
+    let file = map.get(id);
+
+    assert_eq!(file.line_col(11), (1, 11));
+    assert_eq!(file.line_col(17), (2, 1));
+    assert_eq!(file.line_start(2), 17);
+}
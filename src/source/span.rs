@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct FileId(pub usize);
+
+/// A region of a source file.
+///
+/// `start` and `end` are byte offsets into the file (`end` is exclusive),
+/// `line` and `col` are the 1-based position of `start`.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Span {
+    pub file: FileId,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+impl Span {
+    pub fn new(file: FileId, start: usize, end: usize, line: usize, col: usize) -> Span {
+        Span {
+            file,
+            start,
+            end,
+            line,
+            col,
+        }
+    }
+
+    /// Returns a span covering `self` through to the end of `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span {
+            end: other.end.max(self.end),
+            ..*self
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
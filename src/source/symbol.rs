@@ -0,0 +1,128 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// An interned string, standing in for an identifier or keyword.
+///
+/// Symbols are a `u32` index into the interner, so copying and comparing them
+/// is as cheap as for an integer. Two symbols are equal exactly when the
+/// strings they were interned from are equal.
+///
+/// The interner lives for the whole compiler session and is per thread, so
+/// symbols shouldn't be sent between threads.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Symbol(u32);
+
+/// The keywords, pre-interned so that the lexer can match on their symbols.
+/// The order must match the constants in [`kw`].
+const KEYWORDS: [&str; 25] = [
+    "fn", "var", "const", "return", "if", "else", "true", "false", "null",
+    "bool", "i8", "i16", "i32", "i64", "f8", "f16", "f32", "f64", "u8", "String",
+    "while", "for", "in", "break", "continue",
+];
+
+pub mod kw {
+    use super::Symbol;
+
+    pub const FN: Symbol = Symbol(0);
+    pub const VAR: Symbol = Symbol(1);
+    pub const CONST: Symbol = Symbol(2);
+    pub const RETURN: Symbol = Symbol(3);
+    pub const IF: Symbol = Symbol(4);
+    pub const ELSE: Symbol = Symbol(5);
+    pub const TRUE: Symbol = Symbol(6);
+    pub const FALSE: Symbol = Symbol(7);
+    pub const NULL: Symbol = Symbol(8);
+    pub const BOOL: Symbol = Symbol(9);
+    pub const I8: Symbol = Symbol(10);
+    pub const I16: Symbol = Symbol(11);
+    pub const I32: Symbol = Symbol(12);
+    pub const I64: Symbol = Symbol(13);
+    pub const F8: Symbol = Symbol(14);
+    pub const F16: Symbol = Symbol(15);
+    pub const F32: Symbol = Symbol(16);
+    pub const F64: Symbol = Symbol(17);
+    pub const U8: Symbol = Symbol(18);
+    pub const STRING: Symbol = Symbol(19);
+    pub const WHILE: Symbol = Symbol(20);
+    pub const FOR: Symbol = Symbol(21);
+    pub const IN: Symbol = Symbol(22);
+    pub const BREAK: Symbol = Symbol(23);
+    pub const CONTINUE: Symbol = Symbol(24);
+}
+
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+impl Interner {
+    fn new() -> Interner {
+        let mut interner = Interner {
+            symbols: HashMap::new(),
+            strings: Vec::new(),
+        };
+        for keyword in KEYWORDS {
+            interner.intern(keyword);
+        }
+
+        interner
+    }
+
+    fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(text) {
+            return *symbol;
+        }
+
+        //  interned strings are never freed, they live as long as the session
+        let text: &'static str = Box::leak(text.to_string().into_boxed_str());
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(text);
+        self.symbols.insert(text, symbol);
+        symbol
+    }
+}
+
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner::new());
+}
+
+impl Symbol {
+    pub fn intern(text: &str) -> Symbol {
+        INTERNER.with(|interner| interner.borrow_mut().intern(text))
+    }
+
+    /// Returns the text the symbol was interned from.
+    pub fn as_str(&self) -> &'static str {
+        INTERNER.with(|interner| interner.borrow().strings[self.0 as usize])
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(text: &str) -> Symbol {
+        Symbol::intern(text)
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[test]
+fn interner_test() {
+    let x = Symbol::intern("x");
+    let café = Symbol::intern("café");
+
+    assert_eq!(x, Symbol::intern("x"));
+    assert_ne!(x, café);
+    assert_eq!(x.as_str(), "x");
+    assert_eq!(café.to_string(), "café");
+    assert!(x.0 as usize >= KEYWORDS.len());
+
+    for (i, keyword) in KEYWORDS.iter().enumerate() {
+        assert_eq!(Symbol::intern(keyword), Symbol(i as u32));
+    }
+    assert_eq!(Symbol::intern("String"), kw::STRING);
+}